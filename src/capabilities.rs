@@ -0,0 +1,58 @@
+use capctl::{ambient, bounding, Cap, CapSet, CapState};
+use oci_spec::runtime::{Capabilities, LinuxCapabilities};
+
+/// Converts the capabilities of the spec. Capabilities, which are unknown to runh or the kernel,
+/// are skipped.
+fn to_capset(capabilities: &Option<Capabilities>) -> CapSet {
+	let mut capset = CapSet::empty();
+	for capability in capabilities.iter().flatten() {
+		// The spec uses the names of the kernel, e.g. CAP_NET_ADMIN
+		let name = serde_json::to_value(capability).unwrap();
+		match name.as_str().unwrap_or_default().parse::<Cap>() {
+			Ok(cap) if cap.is_supported() => capset.add(cap),
+			_ => warn!("Capability {name} is not supported and will be ignored!"),
+		}
+	}
+	capset
+}
+
+/// Drops all capabilities from the bounding set, which are not requested by the spec. This has
+/// to happen before the process gives up CAP_SETPCAP.
+pub fn drop_bounding_set(capabilities: &LinuxCapabilities) {
+	let keep = to_capset(capabilities.bounding());
+	for cap in Cap::iter().filter(|cap| cap.is_supported() && !keep.has(*cap)) {
+		bounding::drop(cap)
+			.unwrap_or_else(|err| panic!("Could not drop {} from bounding set: {}", cap, err));
+	}
+}
+
+/// Sets the effective, permitted, inheritable and ambient capabilities of the spec and drops
+/// all others.
+pub fn set_capabilities(capabilities: &LinuxCapabilities) {
+	let state = CapState {
+		effective: to_capset(capabilities.effective()),
+		permitted: to_capset(capabilities.permitted()),
+		inheritable: to_capset(capabilities.inheritable()),
+	};
+	debug!("Set capabilities {state:?}");
+	state
+		.set_current()
+		.unwrap_or_else(|err| panic!("Could not set capabilities: {}", err));
+
+	if !ambient::is_supported() {
+		if capabilities
+			.ambient()
+			.as_ref()
+			.is_some_and(|caps| !caps.is_empty())
+		{
+			warn!("Ambient capabilities are not supported by the kernel and will be ignored!");
+		}
+		return;
+	}
+	ambient::clear().expect("Could not clear ambient capabilities!");
+	// Ambient capabilities have to be permitted and inheritable
+	for cap in to_capset(capabilities.ambient()) {
+		ambient::raise(cap)
+			.unwrap_or_else(|err| panic!("Could not raise ambient capability {}: {}", cap, err));
+	}
+}
@@ -0,0 +1,343 @@
+use nix::sys::signal::Signal;
+use nix::unistd::{AccessFlags, Pid};
+use oci_spec::runtime::{LinuxResources, LinuxThrottleDevice, Spec};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+const FREEZE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const REMOVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time, which killed container processes get to exit, before runh gives up waiting for them
+pub const KILL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Checks whether the unified cgroup v2 hierarchy is mounted at its default location.
+pub fn is_cgroup2_available() -> bool {
+	Path::new(CGROUP_ROOT).join("cgroup.controllers").exists()
+}
+
+/// Determines the cgroup of a container from `linux.cgroupsPath`.
+/// Relative and absolute paths are both interpreted relative to the cgroup root.
+/// If no path is given, the container is placed in `runh/<id>`.
+pub fn get_cgroup_path(spec: &Spec, id: &str) -> PathBuf {
+	let cgroups_path = spec
+		.linux()
+		.as_ref()
+		.and_then(|linux| linux.cgroups_path().clone())
+		.unwrap_or_else(|| PathBuf::from("runh").join(id));
+	let relative_path = cgroups_path.strip_prefix("/").unwrap_or(&cgroups_path);
+	Path::new(CGROUP_ROOT).join(relative_path)
+}
+
+/// Returns the path of the cgroup v2 the process `pid` belongs to.
+pub fn get_process_cgroup(pid: i32) -> Option<PathBuf> {
+	let content = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+	content
+		.lines()
+		.find_map(|line| line.strip_prefix("0::"))
+		.map(|path| Path::new(CGROUP_ROOT).join(path.trim_start_matches('/')))
+}
+
+/// Checks whether the cgroup can be created or modified by the current user, which is the case if
+/// its closest existing ancestor is writable.
+pub fn is_writable(cgroup: &Path) -> bool {
+	cgroup
+		.ancestors()
+		.find(|path| path.exists())
+		.is_some_and(|path| nix::unistd::access(path, AccessFlags::W_OK).is_ok())
+}
+
+pub fn is_frozen(cgroup: &Path) -> bool {
+	std::fs::read_to_string(cgroup.join("cgroup.events"))
+		.map(|events| events.lines().any(|line| line == "frozen 1"))
+		.unwrap_or(false)
+}
+
+/// Freezes or thaws all processes in the given cgroup and waits until the kernel reports the new state.
+pub fn freeze(cgroup: &Path, frozen: bool) {
+	std::fs::write(cgroup.join("cgroup.freeze"), if frozen { "1" } else { "0" })
+		.unwrap_or_else(|_| panic!("Could not write cgroup.freeze of cgroup {:?}", cgroup));
+
+	let start = Instant::now();
+	while is_frozen(cgroup) != frozen {
+		if start.elapsed() > FREEZE_TIMEOUT {
+			panic!(
+				"Timeout while waiting for cgroup {:?} to change its freezer state!",
+				cgroup
+			);
+		}
+		std::thread::sleep(Duration::from_millis(10));
+	}
+}
+
+fn write_cgroup_file(cgroup: &Path, file: &str, value: &str) {
+	debug!("Write {value} to {file} of cgroup {cgroup:?}");
+	std::fs::write(cgroup.join(file), value).unwrap_or_else(|err| {
+		panic!(
+			"Could not write {} to {} of cgroup {:?}: {}",
+			value, file, cgroup, err
+		)
+	});
+}
+
+/// Creates the cgroup and enables all available controllers on the way down from the cgroup root.
+pub fn create_cgroup(cgroup: &Path) {
+	let relative_path = cgroup
+		.strip_prefix(CGROUP_ROOT)
+		.unwrap_or_else(|_| panic!("Cgroup {:?} is not located in {}", cgroup, CGROUP_ROOT));
+
+	let mut current = PathBuf::from(CGROUP_ROOT);
+	for component in relative_path.iter() {
+		let controllers = std::fs::read_to_string(current.join("cgroup.controllers"))
+			.unwrap_or_else(|_| panic!("Could not read controllers of cgroup {:?}", current));
+		for controller in controllers.split_whitespace() {
+			// Controllers might be unavailable for delegation (e.g. cpu in the presence of realtime tasks)
+			if let Err(err) = std::fs::write(
+				current.join("cgroup.subtree_control"),
+				format!("+{controller}"),
+			) {
+				debug!("Could not enable controller {controller} for cgroup {current:?}: {err}");
+			}
+		}
+
+		current.push(component);
+		if !current.exists() {
+			std::fs::create_dir(&current)
+				.unwrap_or_else(|_| panic!("Could not create cgroup {:?}", current));
+		}
+	}
+}
+
+/// Moves the process `pid` into the cgroup.
+pub fn add_process(cgroup: &Path, pid: i32) {
+	write_cgroup_file(cgroup, "cgroup.procs", &pid.to_string());
+}
+
+/// Returns the PIDs of all processes in the cgroup and its descendants.
+pub fn get_processes(cgroup: &Path) -> Vec<i32> {
+	let mut processes: Vec<i32> = std::fs::read_to_string(cgroup.join("cgroup.procs"))
+		.map(|procs| procs.lines().filter_map(|pid| pid.parse().ok()).collect())
+		.unwrap_or_default();
+	if let Ok(entries) = std::fs::read_dir(cgroup) {
+		for entry in entries.flatten() {
+			if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+				processes.extend(get_processes(&entry.path()));
+			}
+		}
+	}
+	processes
+}
+
+/// Kills all processes in the cgroup and its descendants and waits until they have exited.
+/// Returns false, if processes remain in the cgroup after the timeout.
+pub fn kill_all(cgroup: &Path) -> bool {
+	if !cgroup.exists() {
+		return true;
+	}
+
+	// cgroup.kill is available since Linux 5.14 and is not racing with forking processes
+	if std::fs::write(cgroup.join("cgroup.kill"), "1").is_err() {
+		for pid in get_processes(cgroup) {
+			let _ = nix::sys::signal::kill(Pid::from_raw(pid), Signal::SIGKILL);
+		}
+	}
+
+	let start = Instant::now();
+	while is_populated(cgroup) {
+		if start.elapsed() > KILL_TIMEOUT {
+			return false;
+		}
+		std::thread::sleep(Duration::from_millis(10));
+	}
+	true
+}
+
+fn is_populated(cgroup: &Path) -> bool {
+	std::fs::read_to_string(cgroup.join("cgroup.events"))
+		.map(|events| events.lines().any(|line| line == "populated 1"))
+		.unwrap_or(false)
+}
+
+/// Removes the (empty) cgroup. As the kernel might take a moment to release exited processes,
+/// the removal is retried for a short time.
+pub fn remove_cgroup(cgroup: &Path) {
+	if !cgroup.exists() {
+		return;
+	}
+
+	let start = Instant::now();
+	while let Err(err) = std::fs::remove_dir(cgroup) {
+		if start.elapsed() > REMOVE_TIMEOUT {
+			warn!("Could not remove cgroup {cgroup:?}: {err}");
+			return;
+		}
+		std::thread::sleep(Duration::from_millis(10));
+	}
+}
+
+/// Parses a cpuset list like `0-3,7` into the contained CPU (or memory node) numbers.
+pub fn parse_cpuset(cpuset: &str) -> BTreeSet<usize> {
+	let mut ids = BTreeSet::new();
+	for range in cpuset.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+		let (start, end) = range.split_once('-').unwrap_or((range, range));
+		let start: usize = start
+			.trim()
+			.parse()
+			.unwrap_or_else(|_| panic!("Could not parse cpuset {}", cpuset));
+		let end: usize = end
+			.trim()
+			.parse()
+			.unwrap_or_else(|_| panic!("Could not parse cpuset {}", cpuset));
+		ids.extend(start..=end);
+	}
+	ids
+}
+
+fn limit_to_string(limit: i64) -> String {
+	if limit < 0 {
+		"max".to_string()
+	} else {
+		limit.to_string()
+	}
+}
+
+fn write_io_max(cgroup: &Path, devices: &Option<Vec<LinuxThrottleDevice>>, key: &str) {
+	for device in devices.iter().flatten() {
+		write_cgroup_file(
+			cgroup,
+			"io.max",
+			&format!(
+				"{}:{} {}={}",
+				device.major(),
+				device.minor(),
+				key,
+				device.rate()
+			),
+		);
+	}
+}
+
+/// Applies the resource limits of the OCI runtime spec using the cgroup v2 interface files.
+/// The conversion of cgroup v1 values follows the one used by runc and crun.
+pub fn apply_resources(cgroup: &Path, resources: &LinuxResources) {
+	if let Some(memory) = resources.memory() {
+		if let Some(limit) = memory.limit() {
+			write_cgroup_file(cgroup, "memory.max", &limit_to_string(limit));
+		}
+		if let Some(reservation) = memory.reservation() {
+			write_cgroup_file(cgroup, "memory.low", &limit_to_string(reservation));
+		}
+		if let Some(swap) = memory.swap() {
+			// cgroup v1 limits memory+swap, whereas cgroup v2 limits swap alone
+			let swap_max = match memory.limit() {
+				_ if swap < 0 => -1,
+				Some(limit) if limit > 0 => {
+					if swap < limit {
+						panic!(
+							"Memory+swap limit {} has to be larger than the memory limit {}!",
+							swap, limit
+						);
+					}
+					swap - limit
+				}
+				_ => swap,
+			};
+			write_cgroup_file(cgroup, "memory.swap.max", &limit_to_string(swap_max));
+		}
+		if memory.kernel().is_some() || memory.kernel_tcp().is_some() {
+			warn!("Kernel memory limits are not supported by cgroup v2 and will be ignored!");
+		}
+		if memory.swappiness().is_some() {
+			warn!("Memory swappiness is not supported by cgroup v2 and will be ignored!");
+		}
+		if memory.disable_oom_killer().unwrap_or(false) {
+			warn!("Disabling the OOM killer is not supported by cgroup v2 and will be ignored!");
+		}
+	}
+
+	if let Some(cpu) = resources.cpu() {
+		if let Some(shares) = cpu.shares() {
+			// Convert from [2-262144] to [1-10000]
+			let weight = 1 + ((shares.clamp(2, 262144) - 2) * 9999) / 262142;
+			write_cgroup_file(cgroup, "cpu.weight", &weight.to_string());
+		}
+		if cpu.quota().is_some() || cpu.period().is_some() {
+			let quota = cpu.quota().map_or("max".to_string(), limit_to_string);
+			let period = cpu.period().unwrap_or(100000);
+			write_cgroup_file(cgroup, "cpu.max", &format!("{quota} {period}"));
+		}
+		if let Some(burst) = cpu.burst() {
+			write_cgroup_file(cgroup, "cpu.max.burst", &burst.to_string());
+		}
+		if let Some(idle) = cpu.idle() {
+			write_cgroup_file(cgroup, "cpu.idle", &idle.to_string());
+		}
+		if let Some(cpus) = cpu.cpus() {
+			write_cgroup_file(cgroup, "cpuset.cpus", cpus);
+		}
+		if let Some(mems) = cpu.mems() {
+			write_cgroup_file(cgroup, "cpuset.mems", mems);
+		}
+		if cpu.realtime_runtime().is_some() || cpu.realtime_period().is_some() {
+			warn!("Realtime scheduling limits are not supported by cgroup v2 and will be ignored!");
+		}
+	}
+
+	if let Some(pids) = resources.pids() {
+		let limit = if pids.limit() > 0 { pids.limit() } else { -1 };
+		write_cgroup_file(cgroup, "pids.max", &limit_to_string(limit));
+	}
+
+	if let Some(block_io) = resources.block_io() {
+		if let Some(weight) = block_io.weight() {
+			// Convert from [10-1000] to [1-10000]
+			let weight = 1 + ((weight.clamp(10, 1000) as u64 - 10) * 9999) / 990;
+			write_cgroup_file(cgroup, "io.weight", &format!("default {weight}"));
+		}
+		if block_io.weight_device().is_some() {
+			warn!("Per-device block IO weights are not supported by runh and will be ignored!");
+		}
+		write_io_max(cgroup, block_io.throttle_read_bps_device(), "rbps");
+		write_io_max(cgroup, block_io.throttle_write_bps_device(), "wbps");
+		write_io_max(cgroup, block_io.throttle_read_iops_device(), "riops");
+		write_io_max(cgroup, block_io.throttle_write_iops_device(), "wiops");
+	}
+
+	for hugepage_limit in resources.hugepage_limits().iter().flatten() {
+		write_cgroup_file(
+			cgroup,
+			&format!("hugetlb.{}.max", hugepage_limit.page_size()),
+			&limit_to_string(hugepage_limit.limit()),
+		);
+	}
+
+	if resources.devices().is_some() {
+		debug!("Device cgroup rules require eBPF programs in cgroup v2 and are currently not enforced!");
+	}
+	if resources.network().is_some() {
+		warn!("Network resources are not supported by cgroup v2 and will be ignored!");
+	}
+
+	if let Some(unified) = resources.unified() {
+		for (key, value) in unified {
+			write_cgroup_file(cgroup, key, value);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_cpuset_lists() {
+		assert!(parse_cpuset("").is_empty());
+		assert_eq!(parse_cpuset("3"), BTreeSet::from([3]));
+		assert_eq!(parse_cpuset("0-2, 7"), BTreeSet::from([0, 1, 2, 7]));
+		// Overlapping ranges do not count twice
+		assert_eq!(parse_cpuset("0-3,2-4").len(), 5);
+	}
+}
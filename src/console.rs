@@ -1,4 +1,9 @@
-use std::{fs::File, io::IoSlice, os::unix::prelude::AsRawFd, path::PathBuf};
+use std::{
+	fs::File,
+	io::IoSlice,
+	os::unix::prelude::{AsRawFd, RawFd},
+	path::PathBuf,
+};
 
 use nix::{
 	fcntl::OFlag,
@@ -7,11 +12,16 @@ use nix::{
 
 use crate::mounts;
 
+nix::ioctl_read_bad!(ioctl_get_winsize, libc::TIOCGWINSZ, libc::winsize);
 nix::ioctl_write_ptr_bad!(ioctl_set_winsize, libc::TIOCSWINSZ, libc::winsize);
 
 nix::ioctl_write_int_bad!(ioctl_set_ctty, libc::TIOCSCTTY);
 
-pub fn setup_console(console_socket: File, win_size: Option<&nix::pty::Winsize>) {
+pub fn setup_console(
+	console_socket: File,
+	win_size: Option<&nix::pty::Winsize>,
+	mount_dev_console: bool,
+) {
 	// Open a new PTY master
 	let master_fd = nix::pty::posix_openpt(OFlag::O_RDWR | OFlag::O_CLOEXEC)
 		.expect("Could not open pty master!");
@@ -29,7 +39,10 @@ pub fn setup_console(console_socket: File, win_size: Option<&nix::pty::Winsize>)
 			.expect("Could not set winsize using ioctl!");
 	}
 
-	mounts::mount_console(&slave_name);
+	// Processes started by `runh exec` reuse the /dev/console of the container
+	if mount_dev_console {
+		mounts::mount_console(&slave_name);
+	}
 
 	//Send master fd over console_socket
 	nix::sys::socket::sendmsg::<()>(
@@ -52,3 +65,13 @@ pub fn setup_console(console_socket: File, win_size: Option<&nix::pty::Winsize>)
 
 	//master_fd auto-closes on drop
 }
+
+/// Copies the terminal size of runh to the pty master of the container.
+pub fn copy_winsize(master: RawFd) {
+	let mut winsize: libc::winsize = unsafe { std::mem::zeroed() };
+	if unsafe { ioctl_get_winsize(libc::STDIN_FILENO, &mut winsize) }.is_ok() {
+		if let Err(err) = unsafe { ioctl_set_winsize(master, &winsize) } {
+			warn!("Could not resize container terminal: {err}");
+		}
+	}
+}
@@ -1,5 +1,19 @@
 pub const INIT_REQ_PRESTART_HOOKS: u8 = 0x10;
 pub const CREATE_ACK_PRESTART_HOOKS: u8 = 0x11;
+pub const INIT_REQ_CREATE_RUNTIME_HOOKS: u8 = 0x12;
+/// Followed by the length-prefixed container state for the hooks, which run inside the container
+pub const CREATE_ACK_CREATE_RUNTIME_HOOKS: u8 = 0x13;
+pub const INIT_REQ_ID_MAPPING: u8 = 0x14;
+pub const CREATE_ACK_ID_MAPPING: u8 = 0x15;
 pub const INIT_READY_TO_EXECV: u8 = 0xAA;
 
 pub const OCI_STATE_VERSION: &str = "1.0.2";
+
+/// Location of the QMP socket of Hermit VMs inside the container
+pub const QMP_SOCKET: &str = "/run/runh-qmp.sock";
+
+/// Location of the API socket of Firecracker VMs inside the container
+pub const FIRECRACKER_SOCKET: &str = "/run/runh-firecracker.sock";
+
+/// Location of the VM configuration file of Firecracker inside the container
+pub const FIRECRACKER_CONFIG: &str = "/run/runh-firecracker.json";
@@ -1,10 +1,13 @@
 use std::cmp::Ordering;
+use std::path::PathBuf;
 
-use getset::Getters;
+use crate::hypervisor::HypervisorKind;
+use crate::vmconfig::VmConfig;
+use getset::{CopyGetters, Getters, Setters};
 use oci_spec::runtime::Spec;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Getters, Serialize, Deserialize)]
+#[derive(Debug, CopyGetters, Getters, Setters, Serialize, Deserialize)]
 /// A general OCI container implementation.
 pub struct OCIContainer {
 	#[get = "pub"]
@@ -20,6 +23,30 @@ pub struct OCIContainer {
 	#[get = "pub"]
 	/// OCI Runtime Specification of the container.
 	spec: Spec,
+
+	#[get_copy = "pub"]
+	#[set = "pub"]
+	#[serde(default)]
+	/// Whether the container runs a Hermit application inside a virtual machine.
+	is_hermit_container: bool,
+
+	#[get = "pub"]
+	#[set = "pub"]
+	#[serde(default)]
+	/// Cgroup, which contains all processes of the container.
+	cgroup_path: Option<PathBuf>,
+
+	#[get_copy = "pub"]
+	#[set = "pub"]
+	#[serde(default)]
+	/// Whether the cgroup is managed by systemd as a transient scope unit.
+	systemd_cgroup: bool,
+
+	#[get = "pub"]
+	#[set = "pub"]
+	#[serde(default)]
+	/// Configuration of the virtual machine of a Hermit container.
+	vm_config: Option<VmConfig>,
 }
 
 impl OCIContainer {
@@ -33,8 +60,17 @@ impl OCIContainer {
 			bundle: can_path.to_str().unwrap().to_string(),
 			pidfile,
 			spec: Spec::load(config.to_str().unwrap()).expect("Unable to load config file"),
+			is_hermit_container: false,
+			cgroup_path: None,
+			systemd_cgroup: false,
+			vm_config: None,
 		}
 	}
+
+	/// Hypervisor, which runs the VM of a Hermit container.
+	pub fn hypervisor(&self) -> Option<HypervisorKind> {
+		self.vm_config.as_ref().map(VmConfig::hypervisor)
+	}
 }
 
 impl Eq for OCIContainer {}
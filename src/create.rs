@@ -1,9 +1,21 @@
+use crate::cgroups;
+use crate::flags;
 use crate::hermit;
+use crate::hooks;
+use crate::hypervisor::HypervisorKind;
 use crate::logging::LogLevel;
+use crate::qmp;
+use crate::rlimits;
 use crate::rootfs;
+use crate::rootless;
 use crate::state;
+use crate::systemd;
+use crate::userns;
+use crate::vm;
+use crate::vmconfig::VmConfig;
 use command_fds::{CommandFdExt, FdMapping};
 use nix::fcntl::OFlag;
+use nix::sched::CloneFlags;
 use nix::sys::socket;
 use nix::sys::socket::SockFlag;
 use nix::sys::stat::Mode;
@@ -16,9 +28,8 @@ use std::io::prelude::*;
 use std::os::unix::fs;
 use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::net::UnixStream;
-use std::os::unix::prelude::CommandExt;
+use std::panic::AssertUnwindSafe;
 use std::path::{Path, PathBuf};
-use std::str::FromStr;
 
 use crate::container::OCIContainer;
 
@@ -31,12 +42,14 @@ pub fn create_container(
 	console_socket: Option<PathBuf>,
 	debug_config: bool,
 	child_log_level: LogLevel,
+	inherit_stdin: bool,
+	systemd_cgroup: bool,
 ) {
 	let _ = std::fs::create_dir(&project_dir);
 
 	let container_dir = rootfs::resolve_in_rootfs(Path::new(id), &project_dir);
 	std::fs::create_dir(container_dir.clone()).expect("Unable to create container directory");
-	let container = OCIContainer::new(
+	let mut container = OCIContainer::new(
 		bundle.to_str().unwrap().to_owned(),
 		id.to_string(),
 		pidfile.as_ref().map_or(
@@ -45,34 +58,6 @@ pub fn create_container(
 		),
 	);
 
-	// write container to disk
-	let spec_path = container_dir.join("container.json");
-	let mut file = OpenOptions::new()
-		.read(true)
-		.write(true)
-		.create_new(true)
-		.open(spec_path)
-		.expect("Unable to create container");
-	file.write_all(serde_json::to_string(&container).unwrap().as_bytes())
-		.unwrap();
-
-	// link container bundle
-	fs::symlink(bundle.clone(), container_dir.join("bundle"))
-		.expect("Unable to symlink bundle into project dir!");
-
-	// write container to root dir
-	if debug_config {
-		let spec_path_backup = project_dir.join(format!("container-{id}.json"));
-		let mut file = OpenOptions::new()
-			.write(true)
-			.create(true)
-			.truncate(true)
-			.open(spec_path_backup)
-			.expect("Unable to write spec to backup file!");
-		file.write_all(serde_json::to_string(&container).unwrap().as_bytes())
-			.unwrap();
-	}
-
 	// find rootfs
 	let bundle_rootfs_path = container.spec().root().as_ref().unwrap().path();
 	let bundle_rootfs_path_abs = std::fs::canonicalize(if bundle_rootfs_path.is_absolute() {
@@ -99,14 +84,87 @@ pub fn create_container(
 			.expect("Container spec does not contain any args!"),
 	);
 	let exec_path_abs = rootfs::resolve_in_rootfs(exec_path_rel, &bundle_rootfs_path_abs);
-	let is_hermit_container = if exec_path_abs.exists() {
-		hermit::is_hermit_app(&exec_path_abs)
+	let hermit_architecture = if exec_path_abs.exists() {
+		hermit::get_hermit_architecture(&exec_path_abs)
 	} else {
 		warn!("Could not find args-executable at current point in lifecycle. We will check again later, but hermit executables will NOT be detected!");
-		false
+		None
 	};
-	if is_hermit_container {
-		info!("Detected Hermit OS executable. Creating container in hermit mode!");
+	let is_hermit_container = hermit_architecture.is_some();
+	if let Some(architecture) = hermit_architecture {
+		info!(
+			"Detected Hermit OS executable for {architecture}. Creating container in hermit mode!"
+		);
+		let vm_config = VmConfig::new(container.spec(), architecture, &bundle_rootfs_path_abs);
+		debug!("VM configuration {vm_config:?}");
+		container.set_vm_config(Some(vm_config));
+		// Do not report the exit code of an earlier container with the same rootfs
+		let _ = std::fs::remove_file(bundle_rootfs_path_abs.join(state::EXIT_CODE_FILE));
+	}
+	container.set_is_hermit_container(is_hermit_container);
+
+	if rootless::is_rootless() {
+		debug!("Run container without root privileges");
+		rootless::validate_spec(container.spec());
+		if let Some(vm_config) = container.vm_config() {
+			rootless::validate_vm_config(vm_config);
+		}
+	}
+
+	if let Some(rlimits) = container
+		.spec()
+		.process()
+		.as_ref()
+		.and_then(|process| process.rlimits().as_ref())
+	{
+		rlimits::validate_rlimits(rlimits);
+	}
+
+	// Determine cgroup. It is created as soon as the init process exists. Without root
+	// privileges, only delegated cgroups are usable.
+	if cgroups::is_cgroup2_available()
+		&& rootless::is_rootless()
+		&& !systemd_cgroup
+		&& !cgroups::is_writable(&cgroups::get_cgroup_path(container.spec(), id))
+	{
+		warn!("The cgroup of the container is not writable. Resource limits will not be applied!");
+	} else if cgroups::is_cgroup2_available() {
+		container.set_cgroup_path(Some(if systemd_cgroup {
+			systemd::get_cgroup_path(container.spec(), id)
+		} else {
+			cgroups::get_cgroup_path(container.spec(), id)
+		}));
+		container.set_systemd_cgroup(systemd_cgroup);
+	} else {
+		warn!("cgroup v2 is not available. Resource limits will not be applied!");
+	}
+
+	// write container to disk
+	let spec_path = container_dir.join("container.json");
+	let mut file = OpenOptions::new()
+		.read(true)
+		.write(true)
+		.create_new(true)
+		.open(spec_path)
+		.expect("Unable to create container");
+	file.write_all(serde_json::to_string(&container).unwrap().as_bytes())
+		.unwrap();
+
+	// link container bundle
+	fs::symlink(bundle.clone(), container_dir.join("bundle"))
+		.expect("Unable to symlink bundle into project dir!");
+
+	// write container to root dir
+	if debug_config {
+		let spec_path_backup = project_dir.join(format!("container-{id}.json"));
+		let mut file = OpenOptions::new()
+			.write(true)
+			.create(true)
+			.truncate(true)
+			.open(spec_path_backup)
+			.expect("Unable to write spec to backup file!");
+		file.write_all(serde_json::to_string(&container).unwrap().as_bytes())
+			.unwrap();
 	}
 
 	//Setup exec fifo
@@ -116,12 +174,14 @@ pub fn create_container(
 		.expect("Could not create fifo!");
 
 	let _ = nix::sys::stat::umask(old_mask);
-	nix::unistd::chown(
-		&fifo_location,
-		Some(Uid::from_raw(0)),
-		Some(Gid::from_raw(0)),
-	)
-	.expect("could not call chown!");
+	if !rootless::is_rootless() {
+		nix::unistd::chown(
+			&fifo_location,
+			Some(Uid::from_raw(0)),
+			Some(Gid::from_raw(0)),
+		)
+		.expect("could not call chown!");
+	}
 
 	let fifo = OpenOptions::new()
 		.custom_flags(libc::O_PATH | libc::O_CLOEXEC)
@@ -143,27 +203,7 @@ pub fn create_container(
 	//Setup log pipe
 	let (parent_log_fd, child_log_fd) =
 		nix::unistd::pipe2(OFlag::O_CLOEXEC).expect("Could not create socket pair for log pipe!");
-	let log_forwarder = std::thread::spawn(move || {
-		let log_pipe = File::from(parent_log_fd);
-		let mut reader = std::io::BufReader::new(log_pipe);
-		let mut buffer: Vec<u8> = vec![];
-		while let Ok(bytes_read) = reader.read_until(b"}"[0], &mut buffer) {
-			if bytes_read > 0 {
-				if let Ok(log_entry) =
-					serde_json::from_slice::<crate::logging::LogEntry>(buffer.as_slice())
-				{
-					match log::Level::from_str(log_entry.level.as_str()) {
-						Ok(level) => log!(level, "[INIT] {}", log_entry.msg),
-						Err(_) => info!("[INIT] {}", log_entry.msg),
-					}
-					buffer.clear();
-				}
-			} else {
-				debug!("Read zero bytes from log pipe, closing forwarder...");
-				break;
-			}
-		}
-	});
+	let log_forwarder = crate::logging::spawn_log_forwarder(parent_log_fd);
 
 	//Setup file system
 	let rootfs_path_abs = Cow::from(&bundle_rootfs_path_abs);
@@ -214,13 +254,17 @@ pub fn create_container(
 	};
 
 	#[allow(clippy::zombie_processes)]
-	let _child = std::process::Command::new("/proc/self/exe")
+	let mut child = std::process::Command::new("/proc/self/exe")
 		.arg("-l")
 		.arg(child_log_level.as_str())
 		.arg("--log-format")
 		.arg("json")
 		.arg("init")
-		.stdin(std::process::Stdio::null())
+		.stdin(if inherit_stdin {
+			std::process::Stdio::inherit()
+		} else {
+			std::process::Stdio::null()
+		})
 		.fd_mappings(child_fd_mappings)
 		.expect("Unable to pass fifo fd to child!")
 		.env("RUNH_FIFOFD", "3")
@@ -232,6 +276,38 @@ pub fn create_container(
 		.spawn()
 		.expect("Unable to spawn runh init process");
 
+	// Setup cgroup. All processes cloned by runh init inherit its cgroup.
+	if let Some(cgroup_path) = container.cgroup_path() {
+		debug!("Create cgroup {cgroup_path:?}");
+		let cgroup_setup = std::panic::catch_unwind(AssertUnwindSafe(|| {
+			if container.systemd_cgroup() {
+				systemd::start_scope(container.spec(), id, child.id());
+			} else {
+				cgroups::create_cgroup(cgroup_path);
+				cgroups::add_process(cgroup_path, child.id() as i32);
+			}
+			if let Some(resources) = container
+				.spec()
+				.linux()
+				.as_ref()
+				.and_then(|linux| linux.resources().as_ref())
+			{
+				cgroups::apply_resources(cgroup_path, resources);
+			}
+		}));
+		if let Err(err) = cgroup_setup {
+			// Do not leave runh init behind, which would wait for its configuration forever
+			let _ = child.kill();
+			let _ = child.wait();
+			if container.systemd_cgroup() {
+				systemd::stop_scope(cgroup_path);
+			} else {
+				cgroups::remove_cgroup(cgroup_path);
+			}
+			std::panic::resume_unwind(err);
+		}
+	}
+
 	debug!("Waiting for first message from child...");
 	let mut init_pipe = File::from(parent_socket_fd);
 	let mut buffer: [u8; 1] = [1];
@@ -248,7 +324,7 @@ pub fn create_container(
 		.expect("Could not convert rootfs-path to string!");
 
 	debug!(
-		"Write rootfs-path {} (lenght {}) to init-pipe!",
+		"Write rootfs-path {} (length {}) to init-pipe!",
 		rootfs_path_str,
 		rootfs_path_str.len()
 	);
@@ -268,7 +344,7 @@ pub fn create_container(
 			.expect("Could not convert rootfs-path to string!");
 
 		debug!(
-			"Write bundle rootfs path {} (lenght {}) to init-pipe!",
+			"Write bundle rootfs path {} (length {}) to init-pipe!",
 			bundle_rootfs_path_str,
 			bundle_rootfs_path_str.len()
 		);
@@ -279,6 +355,41 @@ pub fn create_container(
 		init_pipe
 			.write_all(bundle_rootfs_path_str.as_bytes())
 			.expect("Could not write hermit env path to init pipe!");
+
+		let vm_config = serde_json::to_string(container.vm_config().as_ref().unwrap())
+			.expect("Could not serialize VM configuration!");
+		debug!(
+			"Write VM configuration (length {}) to init-pipe!",
+			vm_config.len()
+		);
+		init_pipe
+			.write_all(&vm_config.len().to_le_bytes())
+			.expect("Could not write VM configuration size to init pipe!");
+		init_pipe
+			.write_all(vm_config.as_bytes())
+			.expect("Could not write VM configuration to init pipe!");
+	}
+
+	// A new user namespace has to be configured from the outside
+	let linux_spec = container.spec().linux().as_ref().unwrap();
+	if linux_spec.namespaces().as_ref().is_some_and(|namespaces| {
+		flags::generate_cloneflags(namespaces).contains(CloneFlags::CLONE_NEWUSER)
+	}) {
+		debug!("Waiting for runh init to request the ID mappings of the user namespace");
+		init_pipe
+			.read_exact(&mut buffer)
+			.expect("Could not read from init pipe!");
+		if buffer[0] != crate::consts::INIT_REQ_ID_MAPPING {
+			panic!(
+				"Received invalid signal from runh init! Expected {:x}, got {:x}",
+				crate::consts::INIT_REQ_ID_MAPPING,
+				buffer[0]
+			);
+		}
+		userns::write_id_mappings(child.id() as i32, linux_spec);
+		init_pipe
+			.write_all(&[crate::consts::CREATE_ACK_ID_MAPPING])
+			.expect("Unable to write to init-pipe!");
 	}
 
 	debug!("Waiting for runh init to send grandchild PID");
@@ -292,6 +403,15 @@ pub fn create_container(
 		let mut file = std::fs::File::create(pid_file_path).expect("Could not create pid-File!");
 		write!(file, "{pid}").expect("Could not write to pid-file!");
 	}
+
+	// Make the QMP socket of the VM accessible through the container directory
+	if container.hypervisor() == Some(HypervisorKind::Qemu) {
+		fs::symlink(
+			qmp::get_socket_path(pid),
+			container_dir.join(vm::QMP_SOCKET_LINK),
+		)
+		.expect("Unable to symlink QMP socket into container directory!");
+	}
 	let mut sig_buffer = [0u8];
 
 	//Running pre-start hooks
@@ -318,68 +438,47 @@ pub fn create_container(
 		.expect("Could not create state-file in container dir!");
 	write!(state_file, "{pid}").expect("Could not write pid to state-file!");
 
-	debug!("Running prestart hooks...");
 	if let Some(hooks) = container.spec().hooks().as_ref() {
-		let state = state::State {
-			version: crate::consts::OCI_STATE_VERSION,
-			id: container.id().clone(),
-			status: "created",
-			pid: Some(pid),
-			bundle: container.bundle().clone(),
-			annotations: container.spec().annotations().clone(),
-		};
-
-		if let Some(prestart_hooks) = hooks.prestart().as_ref() {
-			for hook in prestart_hooks {
-				let mut cmd = std::process::Command::new(hook.path());
-				if let Some(args) = &hook.args() {
-					if !args.is_empty() {
-						cmd.arg0(&args[0]);
-					}
-					if args.len() > 1 {
-						cmd.args(&args[1..]);
-					}
-				}
-				if let Some(env) = &hook.env() {
-					for var in env {
-						let (name, value) = var.split_once('=').unwrap_or_else(|| {
-							panic!("Could not parse environment variable: {}", var)
-						});
-						cmd.env(name, value);
-					}
-				}
-				if hook.timeout().is_some() {
-					warn!("The timeout set for prestart hook {:?} is currently unimplemented and will be ignored!", hook.path());
-				}
-				cmd.stderr(std::process::Stdio::piped());
-				cmd.stdin(std::process::Stdio::piped());
-				let mut child = cmd.spawn().unwrap_or_else(|_| {
-					panic!("Unable to spawn prestart hook process {:?}", hook.path())
-				});
-				write!(
-					child.stdin.take().unwrap(),
-					"{}",
-					serde_json::to_string(&state).unwrap()
-				)
-				.expect("Could not write container state to hook process stdin!");
-
-				let ret = child.wait_with_output().unwrap();
-				if !ret.status.success() {
-					panic!(
-						"prestart hook {:?} returned exit status {}. Stderr: {}",
-						hook.path(),
-						ret.status,
-						String::from_utf8(ret.stderr).unwrap()
-					);
-				}
-			}
-		}
+		let state = hooks::get_hook_state(&container, "created", Some(pid));
+		hooks::run_hooks("prestart", hooks.prestart(), &state);
 	}
 
 	init_pipe
 		.write_all(&[crate::consts::CREATE_ACK_PRESTART_HOOKS])
 		.expect("Unable to write to init-pipe!");
 
+	//Running createRuntime hooks
+	debug!("Waiting for runh init to request createRuntime hooks");
+
+	init_pipe
+		.read_exact(&mut sig_buffer)
+		.expect("Could not read from init pipe!");
+	if sig_buffer[0] != crate::consts::INIT_REQ_CREATE_RUNTIME_HOOKS {
+		panic!(
+			"Received invalid signal from runh init! Expected {:x}, got {:x}",
+			crate::consts::INIT_REQ_CREATE_RUNTIME_HOOKS,
+			sig_buffer[0]
+		);
+	}
+
+	let state = hooks::get_hook_state(&container, "creating", Some(pid));
+	if let Some(hooks) = container.spec().hooks().as_ref() {
+		hooks::run_hooks("createRuntime", hooks.create_runtime(), &state);
+	}
+
+	// runh init does not know its PID outside of the container, so that it receives the state
+	// for the createContainer and startContainer hooks from us
+	let state = serde_json::to_string(&state).unwrap();
+	init_pipe
+		.write_all(&[crate::consts::CREATE_ACK_CREATE_RUNTIME_HOOKS])
+		.expect("Unable to write to init-pipe!");
+	init_pipe
+		.write_all(&state.len().to_le_bytes())
+		.expect("Unable to write to init-pipe!");
+	init_pipe
+		.write_all(state.as_bytes())
+		.expect("Unable to write to init-pipe!");
+
 	//Waiting for init
 	debug!("Waiting for runh init to get ready to execv!");
 
@@ -1,14 +1,20 @@
 use nix::mount::MntFlags;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
 // use nix::sched::CloneFlags;
 
+use crate::container::OCIContainer;
+use crate::hooks;
 use crate::kill;
 // use crate::network;
-use crate::state;
+use crate::{cgroups, state, systemd};
 use std::fs;
+use std::io::BufReader;
 // use std::fs::File;
 // use std::io::BufReader;
 // use std::os::unix::prelude::AsRawFd;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 // fn reset_network_namespace(container_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
 // 	let network_file_path = container_dir.join("hermit_network.json");
@@ -26,6 +32,41 @@ use std::path::PathBuf;
 // 	Ok(())
 // }
 
+/// Kills all processes of the container and waits until they have exited.
+fn kill_remaining_processes(container: &OCIContainer, pid: Option<i32>) {
+	if let Some(cgroup_path) = container.cgroup_path() {
+		if !cgroups::kill_all(cgroup_path) {
+			warn!("Timeout while waiting for the processes in cgroup {cgroup_path:?} to exit!");
+		}
+		return;
+	}
+
+	let processes = kill::get_container_processes(container, pid);
+	for pid in processes.iter() {
+		let _ = nix::sys::signal::kill(Pid::from_raw(*pid), Signal::SIGKILL);
+	}
+
+	let start = Instant::now();
+	while processes.iter().any(|pid| is_alive(*pid)) {
+		if start.elapsed() > cgroups::KILL_TIMEOUT {
+			warn!("Timeout while waiting for the container processes to exit!");
+			return;
+		}
+		std::thread::sleep(Duration::from_millis(10));
+	}
+}
+
+fn is_alive(pid: i32) -> bool {
+	procfs::process::Process::new(pid)
+		.and_then(|process| process.stat())
+		.is_ok_and(|stat| {
+			!matches!(
+				stat.state(),
+				Ok(procfs::process::ProcState::Zombie | procfs::process::ProcState::Dead)
+			)
+		})
+}
+
 pub fn delete_container(project_dir: PathBuf, id: &str, force: bool) {
 	if let Some(container_state) = state::get_container_state(project_dir.clone(), id) {
 		if container_state.status != "stopped" {
@@ -33,14 +74,39 @@ pub fn delete_container(project_dir: PathBuf, id: &str, force: bool) {
 				panic!("Tried to delete a container that is not stopped!");
 			} else if container_state.status != "creating" {
 				warn!("Container is still running. Force-deleting...");
-				kill::kill_container(project_dir.clone(), id, "SIGKILL", false);
 			} else {
 				warn!("Container has not finished creation. Force-deleting...");
 			}
 		}
 
+		if let Some(exit_code) = container_state.exit_code {
+			info!("Hermit application of container {id} exited with exit code {exit_code}");
+		}
+
 		let container_dir = project_dir.join(id);
 
+		let container_file = fs::File::open(container_dir.join("container.json"))
+			.expect("Could not open container file!");
+		let container: OCIContainer = serde_json::from_reader(BufReader::new(container_file))
+			.expect("Could not parse container file!");
+
+		// Helpers like virtiofsd might outlive the container process, so that all remaining
+		// processes have to be removed before the rootfs is unmounted
+		kill_remaining_processes(&container, container_state.pid);
+
+		if container.is_hermit_container() {
+			let rootfs = state::get_rootfs(&container, Path::new(&container_state.bundle));
+			let _ = fs::remove_file(rootfs.join(state::EXIT_CODE_FILE));
+		}
+
+		if let Some(cgroup_path) = container.cgroup_path() {
+			if container.systemd_cgroup() {
+				systemd::stop_scope(cgroup_path);
+			} else {
+				cgroups::remove_cgroup(cgroup_path);
+			}
+		}
+
 		let rootfs_overlay_dir = container_dir.join("rootfs/merged");
 		if rootfs_overlay_dir.exists() {
 			nix::mount::umount2(&rootfs_overlay_dir, MntFlags::MNT_DETACH).unwrap_or_else(|_| {
@@ -53,11 +119,13 @@ pub fn delete_container(project_dir: PathBuf, id: &str, force: bool) {
 		// 	Err(err) => warn!("Failed to reset network namespace! Error: {}", err),
 		// }
 
+		if let Some(hooks) = container.spec().hooks().as_ref() {
+			let state = hooks::get_hook_state(&container, "stopped", container_state.pid);
+			hooks::run_hooks_lenient("poststop", hooks.poststop(), &state);
+		}
+
 		// delete all temporary files
 		fs::remove_dir_all(container_dir).expect("Unable to delete container");
-
-	//Additionally to deleting all the files, we should also delete all remaining processes spawned by the container init process.
-	//However, without cgroup support there is currently no real way to do this as we do not know when (and how) the init process will be killed
 	} else {
 		warn!("Container `{id}` doesn't exists! Skipping deletion...");
 	}
@@ -1,6 +1,7 @@
 use std::{convert::TryInto, fs::OpenOptions, os::unix::prelude::OpenOptionsExt, path::Path};
 
 use nix::{
+	errno::Errno,
 	mount::MsFlags,
 	sys::stat::{Mode, SFlag},
 	unistd::{Gid, Uid},
@@ -101,7 +102,6 @@ pub fn create_devices(spec_devices: &Option<Vec<runtime::LinuxDevice>>, rootfs:
 			)
 		}));
 
-		//Just assume we are not in a user namespace and that mknod will work. Error out if it does not (FIXME ?)
 		let node_kind = match dev.typ() {
 			runtime::LinuxDeviceType::C => SFlag::S_IFCHR,
 			runtime::LinuxDeviceType::B => SFlag::S_IFBLK,
@@ -114,8 +114,19 @@ pub fn create_devices(spec_devices: &Option<Vec<runtime::LinuxDevice>>, rootfs:
 			dev.major().try_into().unwrap(),
 			dev.minor().try_into().unwrap(),
 		);
-		nix::sys::stat::mknod(&destination_resolved, node_kind, mode, device)
-			.unwrap_or_else(|_| panic!("Could not create device {:?}!", dev.path()));
+		match nix::sys::stat::mknod(&destination_resolved, node_kind, mode, device) {
+			Ok(_) => {}
+			// Within a user namespace, device nodes of the host have to be bind mounted instead
+			Err(Errno::EPERM) => {
+				debug!(
+					"Could not create device {:?}, bind mount it instead",
+					dev.path()
+				);
+				bind_device(dev.path(), &destination_resolved);
+				continue;
+			}
+			Err(err) => panic!("Could not create device {:?}: {}", dev.path(), err),
+		}
 		nix::unistd::chown(
 			&destination_resolved,
 			dev.uid().map(Uid::from_raw),
@@ -178,23 +189,37 @@ pub fn create_tun(rootfs: &Path, uid: Uid, gid: Gid) {
 		return;
 	}
 
-	//Just assume we are not in a user namespace and that mknod will work. Error out if it does not (FIXME ?)
 	let node_kind = SFlag::S_IFCHR;
 	let mode = Mode::from_bits(0o755u32).unwrap();
 	let device = nix::sys::stat::makedev(10, 200);
-	nix::sys::stat::mknod(&destination_resolved, node_kind, mode, device)
-		.unwrap_or_else(|_| panic!("Could not create device {:?}!", destination_relative));
+	match nix::sys::stat::mknod(&destination_resolved, node_kind, mode, device) {
+		Ok(_) => {}
+		Err(Errno::EPERM) => {
+			bind_device(destination_relative, &destination_resolved);
+			return;
+		}
+		Err(err) => panic!(
+			"Could not create device {:?}: {}",
+			destination_relative, err
+		),
+	}
 	nix::unistd::chown(&destination_resolved, Some(uid), Some(gid))
 		.unwrap_or_else(|_| panic!("Could not chown device {:?}!", destination_relative));
 }
 
-pub fn mount_hermit_devices(rootfs: &Path) {
+/// Makes the KVM device available in the rootfs. Hypervisors, which are not able to fall back
+/// to emulation, require the device.
+pub fn mount_kvm(rootfs: &Path, required: bool) {
 	if std::fs::metadata("/dev/kvm").is_ok() {
 		mount_device(rootfs, Path::new("/dev/kvm"), 10, 232);
+	} else if required {
+		panic!("/dev/kvm doesn't exist, but is required by the hypervisor!");
 	} else {
 		warn!("/dev/kvm doesn't exist and is consequently not supported!");
 	}
+}
 
+pub fn mount_vhost_net(rootfs: &Path) {
 	if std::fs::metadata("/dev/vhost-net").is_ok() {
 		mount_device(rootfs, Path::new("/dev/vhost-net"), 10, 238);
 	} else {
@@ -219,13 +244,18 @@ fn mount_device(rootfs: &Path, destination_rel: &Path, major: u64, minor: u64) {
 		verify_device(&destination, major, minor);
 		return;
 	}
+	bind_device(destination_rel, &destination);
+}
+
+/// Bind mounts the device of the host at `source` to `destination` in the rootfs.
+fn bind_device(source: &Path, destination: &Path) {
 	if !destination.exists() {
 		let _ = OpenOptions::new()
 			.mode(0o755)
 			.create(true)
 			.truncate(true)
 			.write(true)
-			.open(&destination)
+			.open(destination)
 			.unwrap_or_else(|_| {
 				panic!(
 					"Could not create destination for bind mount at {:?}",
@@ -236,9 +266,9 @@ fn mount_device(rootfs: &Path, destination_rel: &Path, major: u64, minor: u64) {
 
 	mounts::mount_with_flags(
 		"bind",
-		destination_rel,
-		destination_rel,
-		&destination,
+		source,
+		source,
+		destination,
 		mounts::MountOptions {
 			mount_flags: MsFlags::MS_BIND,
 			propagation_flags: MsFlags::empty(),
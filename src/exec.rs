@@ -0,0 +1,479 @@
+use crate::container::OCIContainer;
+use crate::logging::LogLevel;
+use crate::{
+	capabilities, cgroups, console, hooks, namespaces, paths, rlimits, seccomp, signals, state,
+	user,
+};
+use capctl::prctl;
+use command_fds::{CommandFdExt, FdMapping};
+use nix::fcntl::OFlag;
+use nix::sched::{self, CloneFlags};
+use nix::sys::socket::{self, SockFlag};
+use nix::sys::wait::WaitStatus;
+use nix::unistd::Pid;
+use oci_spec::runtime;
+use oci_spec::runtime::Spec;
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::os::unix::prelude::{CommandExt, FromRawFd, IntoRawFd, RawFd};
+use std::path::{Path, PathBuf};
+
+fn parse_user(user: &str) -> runtime::User {
+	let (uid, gid) = match user.split_once(':') {
+		Some((uid, gid)) => (uid, Some(gid)),
+		None => (user, None),
+	};
+
+	let mut process_user = runtime::User::default();
+	process_user.set_uid(
+		uid.parse()
+			.unwrap_or_else(|_| panic!("Could not parse user ID {}", uid)),
+	);
+	process_user.set_gid(
+		gid.map(|gid| {
+			gid.parse()
+				.unwrap_or_else(|_| panic!("Could not parse group ID {}", gid))
+		})
+		.unwrap_or(0),
+	);
+	process_user
+}
+
+#[allow(clippy::too_many_arguments)]
+fn get_exec_process(
+	container_process: Option<&runtime::Process>,
+	process_file: Option<PathBuf>,
+	command: Option<String>,
+	command_options: &[String],
+	tty: bool,
+	env: &[String],
+	cwd: Option<PathBuf>,
+	user: Option<String>,
+) -> runtime::Process {
+	// Analogous to runc, all other options are ignored when a process file is given
+	if let Some(process_file) = process_file {
+		let file = File::open(&process_file)
+			.unwrap_or_else(|_| panic!("Could not open process file at {:?}", process_file));
+		return serde_json::from_reader(BufReader::new(file))
+			.unwrap_or_else(|_| panic!("Could not parse process file at {:?}", process_file));
+	}
+
+	let mut process = container_process.cloned().unwrap_or_default();
+
+	let mut args = vec![command.expect("No command given for exec!")];
+	args.extend_from_slice(command_options);
+	process.set_args(Some(args));
+	process.set_terminal(Some(tty));
+	process.set_console_size(None);
+
+	if !env.is_empty() {
+		let mut process_env = process.env().clone().unwrap_or_default();
+		for var in env {
+			let name = var.split_once('=').map_or(var.as_str(), |(name, _)| name);
+			process_env.retain(|x| x.split_once('=').map_or(x.as_str(), |(name, _)| name) != name);
+			process_env.push(var.clone());
+		}
+		process.set_env(Some(process_env));
+	}
+
+	if let Some(cwd) = cwd {
+		process.set_cwd(cwd);
+	}
+
+	if let Some(user) = user {
+		process.set_user(parse_user(&user));
+	}
+
+	process
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn exec_container(
+	project_dir: PathBuf,
+	id: &str,
+	process_file: Option<PathBuf>,
+	command: Option<String>,
+	command_options: &[String],
+	tty: bool,
+	console_socket: Option<PathBuf>,
+	env: &[String],
+	cwd: Option<PathBuf>,
+	user: Option<String>,
+	detach: bool,
+	pidfile: Option<PathBuf>,
+	child_log_level: LogLevel,
+) {
+	let container_state =
+		state::get_container_state(project_dir.clone(), id).unwrap_or_else(|| {
+			panic!(
+				"Could not exec in container {}. Container does not exist!",
+				id
+			)
+		});
+	if container_state.status != "running" {
+		panic!(
+			"Cannot exec in container with state \"{}\"!",
+			container_state.status
+		);
+	}
+	let container_pid = container_state.pid.unwrap();
+
+	let container_file = OpenOptions::new()
+		.read(true)
+		.open(project_dir.join(id).join("container.json"))
+		.expect("Could not open container file!");
+	let container: OCIContainer = serde_json::from_reader(BufReader::new(container_file))
+		.expect("Could not parse container file!");
+
+	let process = get_exec_process(
+		container.spec().process().as_ref(),
+		process_file,
+		command,
+		command_options,
+		tty,
+		env,
+		cwd,
+		user,
+	);
+	let terminal = process.terminal().unwrap_or(false);
+
+	let mut spec = container.spec().clone();
+	spec.set_process(Some(process));
+
+	// The seccomp agent is only reachable through the runtime mount namespace
+	let seccomp_listener = spec
+		.linux()
+		.as_ref()
+		.and_then(|linux| linux.seccomp().as_ref())
+		.and_then(seccomp::connect_listener);
+
+	//Setup init pipe
+	let (parent_socket_fd, child_socket_fd) = socket::socketpair(
+		socket::AddressFamily::Unix,
+		socket::SockType::Stream,
+		None,
+		SockFlag::SOCK_CLOEXEC,
+	)
+	.expect("Could not create socket pair for init pipe!");
+
+	//Setup log pipe
+	let (parent_log_fd, child_log_fd) =
+		nix::unistd::pipe2(OFlag::O_CLOEXEC).expect("Could not create socket pair for log pipe!");
+	let log_forwarder = crate::logging::spawn_log_forwarder(parent_log_fd);
+
+	let mut child_fd_mappings = vec![
+		FdMapping {
+			parent_fd: child_socket_fd,
+			child_fd: 4,
+		},
+		FdMapping {
+			parent_fd: child_log_fd,
+			child_fd: 6,
+		},
+	];
+
+	//Setup console socket
+	if terminal {
+		let console_socket_path =
+			console_socket.expect("A console socket is required to exec with a terminal!");
+		let stream = UnixStream::connect(&console_socket_path).unwrap_or_else(|_| {
+			panic!(
+				"Could not connect to socket named by console-socket path at {:?}",
+				console_socket_path
+			)
+		});
+		child_fd_mappings.push(FdMapping {
+			parent_fd: stream.into(),
+			child_fd: 7,
+		});
+	}
+
+	let has_seccomp_listener = seccomp_listener.is_some();
+	if let Some(listener) = seccomp_listener {
+		child_fd_mappings.push(FdMapping {
+			parent_fd: listener.into(),
+			child_fd: 8,
+		});
+	}
+
+	let mut child_cmd = std::process::Command::new("/proc/self/exe");
+	child_cmd
+		.arg("-l")
+		.arg(child_log_level.as_str())
+		.arg("--log-format")
+		.arg("json")
+		.arg("init")
+		.fd_mappings(child_fd_mappings)
+		.expect("Unable to pass init pipe fd to child!")
+		.env("RUNH_INITPIPE", "4")
+		.env("RUNH_LOG_PIPE", "6")
+		.env("RUNH_CONSOLE", "7")
+		.env("RUNH_EXEC_PID", container_pid.to_string());
+	if has_seccomp_listener {
+		child_cmd.env("RUNH_SECCOMP_LISTENER", "8");
+	}
+	let mut child = child_cmd
+		.spawn()
+		.expect("Unable to spawn runh init process");
+
+	if let Some(cgroup_path) = container.cgroup_path() {
+		cgroups::add_process(cgroup_path, child.id() as i32);
+	}
+
+	debug!("Waiting for first message from child...");
+	let mut init_pipe = File::from(parent_socket_fd);
+	let mut buffer: [u8; 1] = [1];
+	init_pipe
+		.read_exact(&mut buffer)
+		.expect("Could not read from init pipe!");
+
+	// send spec with the process to execute to child
+	let spec_str = serde_json::to_string(&spec).expect("Could not serialize exec spec!");
+	init_pipe
+		.write_all(&spec_str.len().to_le_bytes())
+		.expect("Could not write spec size to init pipe!");
+	init_pipe
+		.write_all(spec_str.as_bytes())
+		.expect("Could not write spec to init pipe!");
+
+	// The seccomp agent receives the state of the container, which runh init does not know
+	let state = hooks::get_hook_state(&container, "running", Some(container_pid));
+	let state_str = serde_json::to_string(&state).unwrap();
+	init_pipe
+		.write_all(&state_str.len().to_le_bytes())
+		.expect("Could not write state size to init pipe!");
+	init_pipe
+		.write_all(state_str.as_bytes())
+		.expect("Could not write state to init pipe!");
+
+	debug!("Waiting for runh init to send the PID of the new process");
+	let mut pid_buffer = [0; 4];
+	if let Err(x) = init_pipe.read_exact(&mut pid_buffer) {
+		log_forwarder.join().expect("Log forwarder did panic!");
+		panic!("Could not read from init-pipe! Init probably died: {}", x);
+	}
+	let pid = i32::from_le_bytes(pid_buffer);
+	if let Some(pid_file_path) = pidfile {
+		let mut file = std::fs::File::create(pid_file_path).expect("Could not create pid-File!");
+		write!(file, "{pid}").expect("Could not write to pid-file!");
+	}
+
+	let mut sig_buffer = [0u8];
+	if let Err(x) = init_pipe.read_exact(&mut sig_buffer) {
+		log_forwarder.join().expect("Log forwarder did panic!");
+		panic!("Could not read from init-pipe! Init probably died: {}", x);
+	} else if sig_buffer[0] == crate::consts::INIT_READY_TO_EXECV {
+		debug!("Runh init is ready to execv. Waiting for log pipe to close...");
+		log_forwarder.join().expect("Log forwarder did panic!");
+	} else {
+		panic!("Received invalid signal from runh init!");
+	}
+	child.wait().expect("Could not wait for runh init process!");
+
+	if detach {
+		return;
+	}
+
+	// Signals to runh are meant for the attached process
+	signals::forward_signals(Pid::from_raw(pid), None);
+
+	// The new process is a direct child of this process (see CLONE_PARENT in init_exec)
+	match nix::sys::wait::waitpid(Pid::from_raw(pid), None) {
+		Ok(WaitStatus::Exited(_, code)) => std::process::exit(code),
+		Ok(WaitStatus::Signaled(_, signal, _)) => std::process::exit(128 + signal as i32),
+		Ok(status) => panic!("Unexpected wait status {:?} for exec process!", status),
+		Err(err) => panic!("Could not wait for exec process: {}", err),
+	}
+}
+
+pub fn init_exec(container_pid: i32) {
+	let pipe_fd: i32 = env::var("RUNH_INITPIPE")
+		.expect("No init pipe given!")
+		.parse()
+		.expect("RUNH_INITPIPE was not an integer!");
+
+	let mut init_pipe = unsafe { File::from_raw_fd(pipe_fd) };
+	write!(init_pipe, "\0").expect("Unable to write to init-pipe!");
+
+	//Read spec from init pipe
+	let mut size_buffer = [0u8; std::mem::size_of::<usize>()];
+	init_pipe
+		.read_exact(&mut size_buffer)
+		.expect("Could not read message size from init-pipe!");
+	let mut spec_buffer = vec![0; usize::from_le_bytes(size_buffer)];
+	init_pipe
+		.read_exact(&mut spec_buffer)
+		.expect("Could not read spec from init pipe!");
+	let spec: Spec = serde_json::from_slice(&spec_buffer).expect("Unable to parse exec spec!");
+
+	init_pipe
+		.read_exact(&mut size_buffer)
+		.expect("Could not read message size from init-pipe!");
+	let mut state_buffer = vec![0; usize::from_le_bytes(size_buffer)];
+	init_pipe
+		.read_exact(&mut state_buffer)
+		.expect("Could not read container state from init pipe!");
+	let state: serde_json::Value =
+		serde_json::from_slice(&state_buffer).expect("Could not parse container state!");
+
+	debug!("set process as non-dumpable");
+	prctl::set_dumpable(false).expect("Could not set process as non-dumpable!");
+
+	if let Some(namespaces) = spec.linux().as_ref().unwrap().namespaces() {
+		namespaces::join_namespaces(&namespaces::get_process_namespaces(
+			container_pid,
+			namespaces,
+		));
+	}
+
+	// Fork again to actually enter the PID namespace and send PID to parent
+	let init_pipe_fd = init_pipe.into_raw_fd();
+	let stack = vec![0; crate::init::STACK_SIZE].leak();
+	let cb = Box::new(|| exec_stage_child(init_pipe_fd, &spec, &state));
+	let child_pid =
+		unsafe { sched::clone(cb, stack, CloneFlags::CLONE_PARENT, Some(libc::SIGCHLD)) }
+			.unwrap()
+			.as_raw();
+
+	debug!("Send exec PID to runh exec");
+	let mut init_pipe = unsafe { File::from_raw_fd(init_pipe_fd) };
+	init_pipe
+		.write_all(&child_pid.to_le_bytes())
+		.expect("Unable to write to init-pipe!");
+}
+
+fn exec_stage_child(init_pipe_fd: RawFd, spec: &Spec, state: &serde_json::Value) -> isize {
+	let process = spec.process().as_ref().unwrap();
+	let _ = prctl::set_name("runh:EXEC");
+	debug!("Entered container as PID {}", Pid::this());
+
+	let _ = nix::unistd::setsid().expect("Could not set session ID");
+
+	//Safe log_pipe_fd, so we can close it after setup is done.
+	let log_pipe_fd: Option<RawFd> = env::var("RUNH_LOG_PIPE").ok().map(|log_fd| {
+		log_fd
+			.parse::<i32>()
+			.expect("RUNH_LOG_PIPE was not an integer!")
+	});
+
+	if process.terminal().unwrap_or(false) {
+		let console_fd: i32 = env::var("RUNH_CONSOLE")
+			.expect("No console fd given!")
+			.parse()
+			.expect("RUNH_CONSOLE was not an integer!");
+		let console_socket = unsafe { File::from_raw_fd(console_fd) };
+		let win_size = process.console_size().as_ref().map(|b| nix::pty::Winsize {
+			ws_row: b.height() as u16,
+			ws_col: b.width() as u16,
+			ws_xpixel: 0,
+			ws_ypixel: 0,
+		});
+		console::setup_console(console_socket, win_size.as_ref(), false);
+	}
+
+	// runh exec only passes a listener, if the seccomp profile forwards system calls to an agent
+	let mut seccomp_listener = env::var("RUNH_SECCOMP_LISTENER").ok().map(|listener_fd| {
+		let listener_fd = listener_fd
+			.parse::<i32>()
+			.expect("RUNH_SECCOMP_LISTENER was not an integer!");
+		unsafe { UnixStream::from_raw_fd(listener_fd) }
+	});
+
+	unsafe {
+		libc::clearenv();
+	}
+
+	// Set environment variables found in the process config
+	if let Some(env) = process.env() {
+		for var in env {
+			let (name, value) = var
+				.split_once('=')
+				.unwrap_or_else(|| panic!("Could not parse environment variable: {}", var));
+			if !name.is_empty() {
+				std::env::set_var(name, value);
+			}
+		}
+	}
+
+	// Without no_new_privileges, loading the filter requires CAP_SYS_ADMIN, which the process
+	// may lose with its capabilities
+	let seccomp = spec
+		.linux()
+		.as_ref()
+		.and_then(|linux| linux.seccomp().as_ref());
+	let no_new_privileges = process.no_new_privileges().unwrap_or(false);
+	if let Some(seccomp) = seccomp.filter(|_| !no_new_privileges) {
+		seccomp::load_filter(seccomp, seccomp_listener.take(), state);
+	}
+
+	if let Some(rlimits) = process.rlimits() {
+		rlimits::set_rlimits(rlimits);
+	}
+
+	// The bounding set can only be changed with CAP_SETPCAP, which is lost with the user ID
+	if let Some(capabilities) = process.capabilities() {
+		capabilities::drop_bounding_set(capabilities);
+		prctl::set_keepcaps(true).expect("Could not set keepcaps flag!");
+	}
+
+	user::switch_user(process.user());
+
+	if let Some(capabilities) = process.capabilities() {
+		capabilities::set_capabilities(capabilities);
+	}
+
+	let cwd = process.cwd();
+	if !cwd.as_os_str().is_empty() {
+		nix::unistd::chdir(cwd)
+			.unwrap_or_else(|_| panic!("Could not change directory to cwd {:?}", cwd));
+	}
+
+	if no_new_privileges {
+		debug!("set no_new_privileges");
+		prctl::set_no_new_privs().expect("Could not set no_new_privs flag!");
+	}
+
+	let exec_args = process
+		.args()
+		.as_ref()
+		.expect("Exec process does not contain any args!");
+	let exec_path_rel = Path::new(
+		exec_args
+			.first()
+			.expect("Exec process does not contain any args!"),
+	);
+	let exec_path_abs = paths::find_in_path(exec_path_rel, None)
+		.expect("Could not determine location of args-executable!");
+	info!("Running command {}", exec_args.join(" "));
+
+	//Tell runh exec we are ready to execv
+	let mut init_pipe = unsafe { File::from_raw_fd(init_pipe_fd) };
+	init_pipe
+		.write_all(&[crate::consts::INIT_READY_TO_EXECV])
+		.expect("Unable to write to init-pipe!");
+	nix::unistd::close(init_pipe.into_raw_fd()).expect("Could not close init pipe fd!");
+
+	//Close log pipe. All log calls after this should fail due to the log file being closed.
+	if let Some(log_pipe_fd) = log_pipe_fd {
+		nix::unistd::close(log_pipe_fd).expect("Could not close log pipe fd!");
+	}
+
+	let mut cmd = std::process::Command::new(exec_path_abs);
+	cmd.arg0(exec_args.first().unwrap());
+	if exec_args.len() > 1 {
+		cmd.args(exec_args.get(1..).unwrap());
+	}
+	cmd.envs(std::env::vars());
+
+	if let Some(seccomp) = seccomp.filter(|_| no_new_privileges) {
+		seccomp::load_filter(seccomp, seccomp_listener.take(), state);
+	}
+
+	// use implicitly execvp => on success, this function doesn't return
+	let error = cmd.exec();
+
+	//This point should not be reached on successful exec
+	panic!("exec failed with error {}", error)
+}
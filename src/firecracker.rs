@@ -0,0 +1,248 @@
+use crate::consts::{FIRECRACKER_CONFIG, FIRECRACKER_SOCKET};
+use crate::devices;
+use crate::hermit::{Architecture, NetworkConfig};
+use crate::hypervisor::Hypervisor;
+use crate::network;
+use crate::vmconfig::{VmConfig, VmNetwork};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::io::RawFd;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{error::Error, fmt};
+
+const API_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Name of the tap device, which is passed to Firecracker
+const TAP_DEVICE: &str = "tap0";
+
+#[derive(Debug)]
+struct FirecrackerError {
+	details: String,
+}
+
+impl From<String> for FirecrackerError {
+	fn from(msg: String) -> Self {
+		FirecrackerError { details: msg }
+	}
+}
+
+impl fmt::Display for FirecrackerError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.details)
+	}
+}
+
+impl Error for FirecrackerError {
+	fn description(&self) -> &str {
+		&self.details
+	}
+}
+
+/// Returns the path under which the API socket of the VM running as `pid` is reachable from the host.
+pub fn get_socket_path(pid: i32) -> PathBuf {
+	PathBuf::from(format!("/proc/{pid}/root"))
+		.join(Path::new(FIRECRACKER_SOCKET).strip_prefix("/").unwrap())
+}
+
+/// Sends a request to the HTTP API of the Firecracker VM listening on `socket`.
+fn request(
+	socket: &Path,
+	method: &str,
+	path: &str,
+	body: Option<Value>,
+) -> Result<Value, Box<dyn Error>> {
+	let mut stream = UnixStream::connect(socket)?;
+	stream.set_read_timeout(Some(API_TIMEOUT))?;
+	stream.set_write_timeout(Some(API_TIMEOUT))?;
+
+	let body = body.map(|body| body.to_string()).unwrap_or_default();
+	write!(
+		stream,
+		"{method} {path} HTTP/1.1\r\nHost: localhost\r\nAccept: application/json\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+		body.len()
+	)?;
+
+	let mut reader = BufReader::new(stream);
+	let mut status_line = String::new();
+	reader.read_line(&mut status_line)?;
+	let status: u16 = status_line
+		.split_whitespace()
+		.nth(1)
+		.and_then(|status| status.parse().ok())
+		.ok_or_else(|| FirecrackerError::from(format!("Invalid response {status_line:?}")))?;
+
+	let mut content_length = 0;
+	loop {
+		let mut header = String::new();
+		reader.read_line(&mut header)?;
+		let header = header.trim_end();
+		if header.is_empty() {
+			break;
+		}
+		if let Some((name, value)) = header.split_once(':') {
+			if name.eq_ignore_ascii_case("content-length") {
+				content_length = value.trim().parse()?;
+			}
+		}
+	}
+
+	let mut content = vec![0; content_length];
+	reader.read_exact(&mut content)?;
+	let response = if content.is_empty() {
+		Value::Null
+	} else {
+		serde_json::from_slice(&content)?
+	};
+
+	if !(200..300).contains(&status) {
+		return Err(Box::new(FirecrackerError::from(format!(
+			"{method} {path} failed with status {status}: {response}"
+		))));
+	}
+	Ok(response)
+}
+
+/// Pauses or resumes the vCPUs of the Firecracker VM running as `pid`.
+pub fn set_paused(pid: i32, paused: bool) -> Result<(), Box<dyn Error>> {
+	let state = if paused { "Paused" } else { "Resumed" };
+	request(
+		&get_socket_path(pid),
+		"PATCH",
+		"/vm",
+		Some(json!({ "state": state })),
+	)?;
+	Ok(())
+}
+
+/// Firecracker boots the Hermit loader with the application as initrd. The VM is configured
+/// through a configuration file at startup, whereas the API socket is used to control the VM
+/// afterwards. As the container process is Firecracker itself, its exit status is the exit status
+/// of the container.
+pub struct Firecracker;
+
+impl Hypervisor for Firecracker {
+	fn supports_architecture(&self, architecture: Architecture) -> bool {
+		architecture == Architecture::host() && architecture != Architecture::Riscv64
+	}
+
+	fn mount_devices(&self, rootfs: &Path) {
+		devices::mount_kvm(rootfs, true);
+	}
+
+	fn default_network(&self) -> VmNetwork {
+		VmNetwork::Tap
+	}
+
+	/// Firecracker does not provide a user-mode network stack like QEMU.
+	fn supports_network(&self, network: VmNetwork) -> bool {
+		!matches!(network, VmNetwork::User(_))
+	}
+
+	fn create_network(
+		&self,
+		vm_config: &VmConfig,
+		tokio_runtime: &tokio::runtime::Runtime,
+	) -> NetworkConfig {
+		match vm_config.network() {
+			VmNetwork::Tap => {
+				match tokio_runtime.block_on(network::create_redirect_tap(TAP_DEVICE)) {
+					Ok(config) => NetworkConfig::TapDevice(config),
+					Err(err) => {
+						warn!("Hermit network setup could not be completed: {err}");
+						NetworkConfig::None
+					}
+				}
+			}
+			// User networking is rejected during create
+			VmNetwork::User(_) | VmNetwork::None => NetworkConfig::None,
+		}
+	}
+
+	fn request_shutdown(&self, vm_config: &VmConfig) -> bool {
+		// Firecracker is only able to emulate a keyboard reset on x86_64
+		if vm_config.architecture() != Architecture::X86_64 {
+			return false;
+		}
+		match request(
+			Path::new(FIRECRACKER_SOCKET),
+			"PUT",
+			"/actions",
+			Some(json!({ "action_type": "SendCtrlAltDel" })),
+		) {
+			Ok(_) => true,
+			Err(err) => {
+				warn!("Could not request shutdown of the VM: {err}");
+				false
+			}
+		}
+	}
+
+	fn get_args(
+		&self,
+		app: &str,
+		app_args: &[String],
+		netconf: &NetworkConfig,
+		vm_config: &VmConfig,
+		_tap_fd: Option<RawFd>,
+	) -> Vec<String> {
+		if vm_config.micro_vm() {
+			warn!("Firecracker always starts a microvm, so the microvm option is ignored!");
+		}
+		if vm_config.cpu_model().is_some() {
+			warn!("Firecracker always passes the host CPU to the VM, so the CPU model is ignored!");
+		}
+
+		let kernel_path = Path::new(app)
+			.parent()
+			.expect("App path does not have a parent!")
+			.join("hermit-loader");
+
+		let mut boot_args = String::new();
+		let mut network_interfaces = Vec::new();
+		if let NetworkConfig::TapDevice(tap_config) = netconf {
+			boot_args = format!(
+				"-ip {} -gateway {} -mask {}",
+				tap_config.ip, tap_config.gateway, tap_config.mask
+			);
+			network_interfaces.push(json!({
+				"iface_id": "eth0",
+				"guest_mac": tap_config.mac,
+				"host_dev_name": tap_config.name,
+			}));
+		}
+		if let Some(extra_kernel_args) = vm_config.extra_kernel_args() {
+			boot_args = format!("{boot_args} {extra_kernel_args}");
+		}
+		if let Some(application_args) = app_args.get(1..) {
+			boot_args = format!("{} -- {}", boot_args, application_args.join(" "));
+		}
+
+		let config = json!({
+			"boot-source": {
+				"kernel_image_path": kernel_path,
+				"initrd_path": app,
+				"boot_args": boot_args.trim(),
+			},
+			"drives": [],
+			"machine-config": {
+				"vcpu_count": vm_config.smp(),
+				"mem_size_mib": vm_config.memory_size(),
+				"smt": false,
+			},
+			"network-interfaces": network_interfaces,
+		});
+		debug!("Firecracker configuration {config}");
+		std::fs::write(FIRECRACKER_CONFIG, config.to_string())
+			.expect("Could not write Firecracker configuration!");
+
+		vec![
+			"firecracker".to_string(),
+			"--api-sock".to_string(),
+			FIRECRACKER_SOCKET.to_string(),
+			"--config-file".to_string(),
+			FIRECRACKER_CONFIG.to_string(),
+		]
+	}
+}
@@ -1,167 +1,68 @@
 use crate::network;
 use goblin::elf;
+use goblin::elf::header::{EM_AARCH64, EM_RISCV, EM_X86_64};
 use goblin::elf64::header::EI_OSABI;
-use std::{fs, path::Path};
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::Path};
 
-pub fn is_hermit_app(path: &Path) -> bool {
-	let buffer = fs::read(path)
-		.unwrap_or_else(|_| panic!("Could not read content of args-executable at {:?}", path));
-	if let Ok(elf) = elf::Elf::parse(&buffer) {
-		elf.header.e_ident[EI_OSABI] == 0xFF
-	} else {
-		warn!("Could not parse content of args-executable in ELF format. Might be a script file. Assuming non-hermit container...");
-		false
-	}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Architecture {
+	X86_64,
+	Aarch64,
+	Riscv64,
 }
 
-#[derive(Debug)]
-pub enum NetworkConfig {
-	TapNetwork(network::VirtioNetworkConfig),
-	UserNetwork(u16),
-	None,
-}
-
-pub fn get_qemu_args(
-	kernel: &str,
-	app: &str,
-	netconf: &NetworkConfig,
-	app_args: &[String],
-	micro_vm: bool,
-	kvm_support: bool,
-	tap_fd: &Option<i32>,
-) -> Vec<String> {
-	let smp: u32 = crate::CONFIG.smp.unwrap_or(1);
-	let memory_size: String = if let Some(memory_size) = crate::CONFIG.memory_size {
-		format!("{memory_size}M")
-	} else {
-		"1G".to_string()
-	};
-
-	let mut exec_args: Vec<String> = vec![
-		"qemu-system-x86_64",
-		"-display",
-		"none",
-		"-smp",
-		&smp.to_string(),
-		"-m",
-		&memory_size,
-		"-serial",
-		"stdio",
-		"-device",
-		"isa-debug-exit,iobase=0xf4,iosize=0x04",
-		"-kernel",
-		kernel,
-		"-initrd",
-		app,
-	]
-	.into_iter()
-	.map(|s| s.to_string())
-	.collect();
-
-	if let Some(kvm) = crate::CONFIG.kvm {
-		if kvm && kvm_support {
-			exec_args.extend(
-				["--enable-kvm", "-cpu", "host"]
-					.iter()
-					.map(|s| s.to_string()),
-			);
+impl Architecture {
+	pub fn host() -> Self {
+		if cfg!(target_arch = "aarch64") {
+			Self::Aarch64
+		} else if cfg!(target_arch = "riscv64") {
+			Self::Riscv64
 		} else {
-			exec_args.extend(
-				[
-					"-cpu",
-					"qemu64,apic,fsgsbase,rdtscp,xsave,xsaveopt,fxsr,rdrand",
-				]
-				.iter()
-				.map(|s| s.to_string()),
-			);
+			Self::X86_64
 		}
-	} else {
-		// disable kvm support, if the configuration file doesn't enable it
-		exec_args.extend(
-			[
-				"-cpu",
-				"qemu64,apic,fsgsbase,rdtscp,xsave,xsaveopt,fxsr,rdrand",
-			]
-			.iter()
-			.map(|s| s.to_string()),
-		);
-	}
-
-	if micro_vm {
-		exec_args.extend(
-			[
-				"-M",
-				"microvm,x-option-roms=off,pit=off,pic=off,rtc=on,auto-kernel-cmdline=off,acpi=off",
-				"-global",
-				"virtio-mmio.force-legacy=off",
-				"-nodefaults",
-				"-no-user-config",
-			]
-			.iter()
-			.map(|s| s.to_string()),
-		);
-	} else {
-		exec_args.extend(
-			[
-				"-chardev",
-				"socket,id=char0,path=/run/vhostqemu",
-				"-device",
-				"vhost-user-fs-pci,queue-size=1024,chardev=char0,tag=root",
-				"-object",
-				"memory-backend-file,id=mem,size=1G,mem-path=/dev/shm,share=on",
-				"-numa",
-				"node,memdev=mem",
-			]
-			.iter()
-			.map(|s| s.to_string()),
-		);
 	}
+}
 
-	let mut args_string = match netconf {
-		NetworkConfig::TapNetwork(network_config) => {
-			exec_args.push("-netdev".to_string());
-			exec_args.push(format!("tap,id=net0,fd={}", tap_fd.unwrap()));
-			exec_args.push("-device".to_string());
-			exec_args.push(if micro_vm {
-				format!("virtio-net-device,netdev=net0,mac={}", network_config.mac)
-			} else {
-				format!(
-					"virtio-net-pci,netdev=net0,disable-legacy=on,mac={}",
-					network_config.mac
-				)
-			});
-			exec_args.push("-append".to_string());
-
-			let args_string = format!(
-				"-ip {} -gateway {} -mask {}",
-				network_config.ip, network_config.gateway, network_config.mask
-			);
-
-			args_string
+impl fmt::Display for Architecture {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::X86_64 => write!(f, "x86_64"),
+			Self::Aarch64 => write!(f, "aarch64"),
+			Self::Riscv64 => write!(f, "riscv64"),
 		}
-		NetworkConfig::UserNetwork(user_port) => {
-			exec_args.extend([
-				"-netdev".to_string(),
-				format!(
-					"user,id=u1,hostfwd=tcp::{user_port}-:{user_port},net=192.168.76.0/24,dhcpstart=192.168.76.9"
-				),
-				"-device".to_string(),
-				"virtio-net-pci,netdev=u1,disable-legacy=on".to_string(),
-				"-append".to_string(),
-			]);
+	}
+}
 
-			"".to_string()
-		}
-		NetworkConfig::None => {
-			exec_args.push("-append".to_string());
-			"".to_string()
-		}
+/// Determines the architecture of the args-executable, if it is a Hermit application.
+pub fn get_hermit_architecture(path: &Path) -> Option<Architecture> {
+	let buffer = fs::read(path)
+		.unwrap_or_else(|_| panic!("Could not read content of args-executable at {:?}", path));
+	let Ok(elf) = elf::Elf::parse(&buffer) else {
+		warn!("Could not parse content of args-executable in ELF format. Might be a script file. Assuming non-hermit container...");
+		return None;
 	};
+	if elf.header.e_ident[EI_OSABI] != 0xFF {
+		return None;
+	}
 
-	if let Some(application_args) = app_args.get(1..) {
-		args_string = format!("{} -- {}", args_string, application_args.join(" "));
+	match elf.header.e_machine {
+		EM_X86_64 => Some(Architecture::X86_64),
+		EM_AARCH64 => Some(Architecture::Aarch64),
+		EM_RISCV => Some(Architecture::Riscv64),
+		machine => panic!(
+			"Hermit application {:?} was built for the unsupported architecture {}!",
+			path,
+			elf::header::machine_to_str(machine)
+		),
 	}
-	exec_args.push(args_string);
+}
 
-	exec_args
+#[derive(Debug)]
+pub enum NetworkConfig {
+	TapNetwork(network::VirtioNetworkConfig),
+	TapDevice(network::TapDeviceConfig),
+	UserNetwork(u16),
+	None,
 }
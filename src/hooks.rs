@@ -0,0 +1,177 @@
+use crate::container::OCIContainer;
+use crate::state::State;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use oci_spec::runtime::Hook;
+use serde::Serialize;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::process::CommandExt;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Time, which runh waits for the end of the stderr output of a hook after the hook has exited
+const STDERR_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Returns the state of the container, which is passed to its hooks.
+pub fn get_hook_state(container: &OCIContainer, status: &'static str, pid: Option<i32>) -> State {
+	State {
+		version: crate::consts::OCI_STATE_VERSION,
+		id: container.id().clone(),
+		status,
+		pid,
+		bundle: container.bundle().clone(),
+		annotations: container.spec().annotations().clone(),
+		exit_code: None,
+	}
+}
+
+fn run_hook(hook: &Hook, state: &impl Serialize) -> Result<(), String> {
+	let mut cmd = std::process::Command::new(hook.path());
+	if let Some(args) = &hook.args() {
+		if !args.is_empty() {
+			cmd.arg0(&args[0]);
+		}
+		if args.len() > 1 {
+			cmd.args(&args[1..]);
+		}
+	}
+	if let Some(env) = &hook.env() {
+		for var in env {
+			let (name, value) = var
+				.split_once('=')
+				.ok_or_else(|| format!("Could not parse environment variable: {var}"))?;
+			cmd.env(name, value);
+		}
+	}
+	let timeout = match hook.timeout() {
+		Some(timeout) if timeout <= 0 => {
+			return Err(format!(
+				"Timeout of hook {:?} has to be positive, but is {timeout}",
+				hook.path()
+			))
+		}
+		timeout => timeout.map(|timeout| Duration::from_secs(timeout as u64)),
+	};
+	// The hook becomes the leader of a new process group, so that it can be killed along with
+	// its children on expiry of the timeout
+	cmd.process_group(0);
+	cmd.stderr(std::process::Stdio::piped());
+	cmd.stdin(std::process::Stdio::piped());
+	let mut child = cmd
+		.spawn()
+		.map_err(|err| format!("Unable to spawn hook process {:?}: {err}", hook.path()))?;
+	// The state is written in the background, as the hook might not read its stdin at all.
+	// A hook, which exits without reading the state, closes the pipe, which is not an error.
+	let mut stdin = child.stdin.take().unwrap();
+	let state = serde_json::to_string(state).unwrap();
+	let (stdin_sender, stdin_receiver) = mpsc::channel();
+	std::thread::spawn(move || {
+		let result = stdin.write_all(state.as_bytes());
+		let _ = stdin_sender.send(result);
+	});
+
+	// Collect stderr in the background, so that a chatty hook does not block on a full pipe.
+	// Children, which are daemonized by the hook, might keep stderr open, so that the reader
+	// is abandoned after a short time and only the output collected so far is reported.
+	let mut stderr = child.stderr.take().unwrap();
+	let stderr_output = Arc::new(Mutex::new(Vec::new()));
+	let (stderr_sender, stderr_receiver) = mpsc::channel();
+	{
+		let stderr_output = stderr_output.clone();
+		std::thread::spawn(move || {
+			let mut buf = [0u8; 4096];
+			while let Ok(len @ 1..) = stderr.read(&mut buf) {
+				stderr_output.lock().unwrap().extend_from_slice(&buf[..len]);
+			}
+			let _ = stderr_sender.send(());
+		});
+	}
+
+	let start = Instant::now();
+	let status = loop {
+		if let Some(status) = child.try_wait().unwrap() {
+			break Some(status);
+		}
+		if timeout.is_some_and(|timeout| start.elapsed() > timeout) {
+			let _ = nix::sys::signal::killpg(Pid::from_raw(child.id() as i32), Signal::SIGKILL);
+			let _ = child.wait();
+			break None;
+		}
+		std::thread::sleep(Duration::from_millis(10));
+	};
+	let _ = stderr_receiver.recv_timeout(STDERR_TIMEOUT);
+	let stderr = std::mem::take(&mut *stderr_output.lock().unwrap());
+
+	if let Ok(Err(err)) = stdin_receiver.try_recv() {
+		if err.kind() != ErrorKind::BrokenPipe {
+			return Err(format!(
+				"Could not write container state to hook process stdin: {err}"
+			));
+		}
+	}
+
+	match status {
+		None => Err(format!(
+			"Hook {:?} did not finish within {}s. Stderr: {}",
+			hook.path(),
+			timeout.unwrap().as_secs(),
+			String::from_utf8_lossy(&stderr)
+		)),
+		Some(status) if !status.success() => Err(format!(
+			"Hook {:?} returned exit status {}. Stderr: {}",
+			hook.path(),
+			status,
+			String::from_utf8_lossy(&stderr)
+		)),
+		Some(_) => Ok(()),
+	}
+}
+
+/// Runs the hooks one after another with the container state on stdin. The first failing hook
+/// aborts the operation.
+pub fn run_hooks(name: &str, hooks: &Option<Vec<Hook>>, state: &impl Serialize) {
+	debug!("Running {name} hooks...");
+	for hook in hooks.iter().flatten() {
+		run_hook(hook, state).unwrap_or_else(|err| panic!("{} hook failed: {}", name, err));
+	}
+}
+
+/// Runs the hooks one after another with the container state on stdin. According to the OCI
+/// runtime specification, failing hooks are only logged and the remaining hooks are still run.
+pub fn run_hooks_lenient(name: &str, hooks: &Option<Vec<Hook>>, state: &impl Serialize) {
+	debug!("Running {name} hooks...");
+	for hook in hooks.iter().flatten() {
+		if let Err(err) = run_hook(hook, state) {
+			warn!("{name} hook failed: {err}");
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use oci_spec::runtime::HookBuilder;
+
+	fn shell_hook(script: &str) -> Hook {
+		HookBuilder::default()
+			.path("/bin/sh")
+			.args(vec!["sh".to_string(), "-c".to_string(), script.to_string()])
+			.timeout(5)
+			.build()
+			.unwrap()
+	}
+
+	#[test]
+	fn hook_without_reading_stdin() {
+		let state = "x".repeat(1024 * 1024);
+		assert!(run_hook(&shell_hook("exit 0"), &state).is_ok());
+	}
+
+	#[test]
+	fn hook_with_daemonized_child() {
+		let start = Instant::now();
+		let result = run_hook(&shell_hook("echo failed >&2; sleep 10 & exit 1"), &"");
+		assert!(start.elapsed() < Duration::from_secs(5));
+		assert!(result.unwrap_err().contains("failed"));
+	}
+}
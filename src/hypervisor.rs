@@ -0,0 +1,134 @@
+use crate::firecracker::Firecracker;
+use crate::hermit::{Architecture, NetworkConfig};
+use crate::network;
+use crate::qemu::Qemu;
+use crate::uhyve::Uhyve;
+use crate::vmconfig::{VmConfig, VmNetwork, DEFAULT_USER_PORT};
+use oci_spec::runtime::LinuxSeccomp;
+use serde::{Deserialize, Serialize};
+use std::os::unix::io::RawFd;
+use std::path::Path;
+
+/// Memory (in MiB), which is reserved for hypervisors with a small footprint like uhyve and
+/// Firecracker
+const DEFAULT_MEMORY_OVERHEAD: u64 = 16;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HypervisorKind {
+	#[default]
+	Qemu,
+	Uhyve,
+	Firecracker,
+}
+
+impl std::str::FromStr for HypervisorKind {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"qemu" => Ok(Self::Qemu),
+			"uhyve" => Ok(Self::Uhyve),
+			"firecracker" => Ok(Self::Firecracker),
+			_ => Err(format!("Unknown hypervisor {s}")),
+		}
+	}
+}
+
+/// A hypervisor, which is able to run a Hermit application inside the container.
+pub trait Hypervisor {
+	/// Checks whether the hypervisor is able to run applications of the given architecture.
+	/// By default, only the architecture of the host is supported.
+	fn supports_architecture(&self, architecture: Architecture) -> bool {
+		architecture == Architecture::host()
+	}
+
+	/// Memory (in MiB), which the hypervisor requires besides the memory of the VM. It is
+	/// subtracted from the memory limit of the container to size the VM.
+	fn memory_overhead(&self) -> u64 {
+		DEFAULT_MEMORY_OVERHEAD
+	}
+
+	/// Checks during create, that the hypervisor is able to run the VM from the container rootfs,
+	/// so that missing binaries are reported before the container is started.
+	fn check_installation(&self, _vm_config: &VmConfig, _rootfs: &Path) {}
+
+	/// Network, which connects the VM, if the container does not request one explicitly.
+	fn default_network(&self) -> VmNetwork {
+		VmNetwork::User(DEFAULT_USER_PORT)
+	}
+
+	/// Checks whether the hypervisor is able to connect the VM to the given network.
+	fn supports_network(&self, _network: VmNetwork) -> bool {
+		true
+	}
+
+	/// Makes the devices, which are required by the hypervisor, available in the rootfs.
+	fn mount_devices(&self, rootfs: &Path);
+
+	/// Sets up the network devices, which connect the VM to the network namespace of the container.
+	fn create_network(
+		&self,
+		vm_config: &VmConfig,
+		tokio_runtime: &tokio::runtime::Runtime,
+	) -> NetworkConfig {
+		match vm_config.network() {
+			VmNetwork::Tap => match tokio_runtime.block_on(network::create_tap()) {
+				Ok(config) => NetworkConfig::TapNetwork(config),
+				Err(err) => {
+					warn!("Hermit network setup could not be completed: {err}");
+					NetworkConfig::None
+				}
+			},
+			VmNetwork::User(user_port) => NetworkConfig::UserNetwork(user_port),
+			VmNetwork::None => NetworkConfig::None,
+		}
+	}
+
+	/// Lets the VM run, once the hypervisor has created it. `created` is called, as soon as the
+	/// VM exists, so that later exits of the hypervisor are attributed to the application.
+	/// Returns false, if the VM could not be started. By default, the hypervisor runs the VM
+	/// right away.
+	fn start_vm(&self, _vm_config: &VmConfig, created: &dyn Fn()) -> bool {
+		created();
+		true
+	}
+
+	/// Translates the exit code of the hypervisor into the exit code of the Hermit application.
+	fn decode_exit_code(&self, _vm_config: &VmConfig, exit_code: i32) -> i32 {
+		exit_code
+	}
+
+	/// Asks the unikernel to shut down gracefully. Returns false, if the hypervisor is not able
+	/// to forward such a request to the VM.
+	fn request_shutdown(&self, _vm_config: &VmConfig) -> bool {
+		false
+	}
+
+	/// Seccomp profile, which restricts the hypervisor process in addition to the profile of
+	/// the container.
+	fn seccomp_profile(&self, _vm_config: &VmConfig) -> Option<LinuxSeccomp> {
+		None
+	}
+
+	/// Starts helper processes, which have to run next to the hypervisor.
+	fn spawn_helpers(&self, _vm_config: &VmConfig) {}
+
+	/// Builds the command line, which starts the application `app` inside the VM.
+	fn get_args(
+		&self,
+		app: &str,
+		app_args: &[String],
+		netconf: &NetworkConfig,
+		vm_config: &VmConfig,
+		tap_fd: Option<RawFd>,
+	) -> Vec<String>;
+}
+
+pub fn get_hypervisor(kind: HypervisorKind) -> Box<dyn Hypervisor> {
+	match kind {
+		HypervisorKind::Qemu => Box::new(Qemu),
+		HypervisorKind::Uhyve => Box::new(Uhyve),
+		HypervisorKind::Firecracker => Box::new(Firecracker),
+	}
+}
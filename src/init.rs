@@ -11,8 +11,9 @@ use std::{
 };
 
 use crate::hermit::NetworkConfig;
-use crate::{console, devices, hermit, mounts};
-use crate::{flags, paths, rootfs};
+use crate::vmconfig::VmConfig;
+use crate::{capabilities, console, devices, exec, hypervisor, mounts};
+use crate::{flags, hooks, paths, rlimits, rootfs, seccomp, supervisor, user};
 use crate::{namespaces, network};
 use capctl::prctl;
 use command_fds::CommandFdExt;
@@ -42,7 +43,7 @@ struct InitConfig {
 	cloneflags: CloneFlags,
 	rootfs: String,
 	bundle_rootfs: String,
-	is_hermit_container: bool,
+	vm_config: Option<VmConfig>,
 }
 
 #[derive(Clone, Debug)]
@@ -51,7 +52,7 @@ struct SetupArgs {
 	config: InitConfig,
 }
 
-const STACK_SIZE: usize = 16384 * 2;
+pub const STACK_SIZE: usize = 16384 * 2;
 
 pub fn init_container() {
 	// This implements the init process functionality,
@@ -70,6 +71,16 @@ pub fn init_container() {
 
 	//TODO: Ensure we are in a cloned binary (prevent CVE-2019-5736)
 
+	//Processes started by runh exec only have to join the namespaces of the container
+	if let Ok(container_pid) = env::var("RUNH_EXEC_PID") {
+		exec::init_exec(
+			container_pid
+				.parse()
+				.expect("RUNH_EXEC_PID was not an integer!"),
+		);
+		return;
+	}
+
 	//Detect hermit container
 	let is_hermit_container: bool = env::var("RUNH_HERMIT_CONTAINER")
 		.expect("No value for RUNH_HERMIT_CONTAINER set!")
@@ -95,8 +106,9 @@ pub fn init_container() {
 		String::from_utf8(rootfs_path_buffer).expect("Could not parse rootfs-path as string!");
 	debug!("read rootfs from init_pipe: {rootfs_path}");
 
-	//Read bundle rootfs from init pipe
+	//Read bundle rootfs and VM configuration from init pipe
 	let mut bundle_rootfs_path = rootfs_path.clone();
+	let mut vm_config = None;
 	if is_hermit_container {
 		init_pipe
 			.read_exact(&mut size_buffer)
@@ -111,6 +123,20 @@ pub fn init_container() {
 		bundle_rootfs_path = String::from_utf8(bundle_rootfs_path_buffer)
 			.expect("Could not parse bundle rootfs path as string!");
 		debug!("read bundle rootfs path from init_pipe: {bundle_rootfs_path}");
+
+		init_pipe
+			.read_exact(&mut size_buffer)
+			.expect("Could not read message size from init-pipe!");
+		let message_size = usize::from_le_bytes(size_buffer);
+		let mut vm_config_buffer = vec![0; message_size];
+		init_pipe
+			.read_exact(&mut vm_config_buffer)
+			.expect("Could not read VM configuration from init pipe!");
+		vm_config = Some(
+			serde_json::from_slice::<VmConfig>(&vm_config_buffer)
+				.expect("Could not parse VM configuration!"),
+		);
+		debug!("read VM configuration from init_pipe: {vm_config:?}");
 	}
 
 	//Read spec file
@@ -165,7 +191,7 @@ pub fn init_container() {
 			cloneflags,
 			rootfs: rootfs_path,
 			bundle_rootfs: bundle_rootfs_path,
-			is_hermit_container,
+			vm_config,
 		},
 	});
 }
@@ -181,13 +207,34 @@ fn init_stage_parent(args: SetupArgs) -> isize {
 		namespaces::join_namespaces(namespaces)
 	}
 
-	//TODO: Unshare user namespace if requested (needs additional clone)
+	// The user namespace is unshared first, so that all other namespaces are owned by it
 	if args.config.cloneflags.contains(CloneFlags::CLONE_NEWUSER) {
-		unimplemented!("User namespaces are currently not supported by runh!")
-	}
+		debug!("unshare user namespace");
+		nix::sched::unshare(CloneFlags::CLONE_NEWUSER).expect("could not unshare user namespace!");
 
-	//TODO: Let parent setup uidmap/gidmap if a user namespace was joined
+		// runh create writes the ID mappings through /proc, which requires a dumpable process
+		prctl::set_dumpable(true).expect("Could not set process as dumpable!");
+		let mut init_pipe =
+			std::mem::ManuallyDrop::new(unsafe { File::from_raw_fd(args.init_pipe) });
+		init_pipe
+			.write_all(&[crate::consts::INIT_REQ_ID_MAPPING])
+			.expect("Unable to write to init-pipe!");
+		let mut sig_buffer = [0u8];
+		init_pipe
+			.read_exact(&mut sig_buffer)
+			.expect("Could not read from init pipe!");
+		if sig_buffer[0] != crate::consts::CREATE_ACK_ID_MAPPING {
+			panic!(
+				"Received invalid signal from runh create! Expected {:x}, got {:x}",
+				crate::consts::CREATE_ACK_ID_MAPPING,
+				sig_buffer[0]
+			);
+		}
+		prctl::set_dumpable(false).expect("Could not set process as non-dumpable!");
+	}
 
+	nix::unistd::setresgid(Gid::from_raw(0), Gid::from_raw(0), Gid::from_raw(0))
+		.expect("could not become root group in user namespace!");
 	nix::unistd::setresuid(Uid::from_raw(0), Uid::from_raw(0), Uid::from_raw(0))
 		.expect("could not become root in user namespace!");
 
@@ -198,6 +245,7 @@ fn init_stage_parent(args: SetupArgs) -> isize {
 	);
 	let mut flags = args.config.cloneflags;
 	flags.remove(CloneFlags::CLONE_NEWCGROUP);
+	flags.remove(CloneFlags::CLONE_NEWUSER);
 	nix::sched::unshare(flags).expect("could not unshare non-user namespaces!");
 
 	// Fork again into new PID-Namespace and send PID to parent
@@ -228,16 +276,20 @@ fn init_stage_child(args: SetupArgs) -> ! {
 	let _ = prctl::set_name("runh:INIT");
 	debug!("Welcome to the container! This is PID {}", Pid::this());
 
+	let hypervisor = args
+		.config
+		.vm_config
+		.as_ref()
+		.map(|vm_config| hypervisor::get_hypervisor(vm_config.hypervisor()));
+
 	// Set SID, UID, GID
 	let _ = nix::unistd::setsid().expect("Could not set session ID");
 	nix::unistd::setuid(Uid::from_raw(0)).expect("Could not set user ID");
 	nix::unistd::setgid(Gid::from_raw(0)).expect("Could not set group ID");
 
-	// TODO: Call setgroups if !is_rootless_euid && is_setgroup (?)
-
-	// Unshare Cgroup namespace if requested to
+	// Unshare Cgroup namespace if requested to. runh create already moved our parent
+	// into the container cgroup, so it becomes the root of the new namespace.
 	if args.config.cloneflags.contains(CloneFlags::CLONE_NEWCGROUP) {
-		// TODO: Synchronize with runh create for cgroup setup
 		nix::sched::unshare(CloneFlags::CLONE_NEWCGROUP)
 			.expect("could not unshare cgroups namespace!");
 	}
@@ -348,8 +400,8 @@ fn init_stage_child(args: SetupArgs) -> ! {
 		devices::setup_dev_symlinks(rootfs_path);
 	}
 
-	if args.config.is_hermit_container {
-		devices::mount_hermit_devices(rootfs_path);
+	if let Some(hypervisor) = &hypervisor {
+		hypervisor.mount_devices(rootfs_path);
 		devices::create_tun(
 			rootfs_path,
 			Uid::from_raw(args.config.spec.process().as_ref().unwrap().user().uid()),
@@ -376,6 +428,34 @@ fn init_stage_child(args: SetupArgs) -> ! {
 		);
 	}
 
+	//Run createRuntime hooks
+	debug!("Signalling parent to run createRuntime hooks");
+	init_pipe
+		.write_all(&[crate::consts::INIT_REQ_CREATE_RUNTIME_HOOKS])
+		.expect("Unable to write to init-pipe!");
+
+	init_pipe
+		.read_exact(&mut sig_buffer)
+		.expect("Could not read from init pipe!");
+	if sig_buffer[0] != crate::consts::CREATE_ACK_CREATE_RUNTIME_HOOKS {
+		panic!(
+			"Received invalid signal from runh create! Expected {:x}, got {:x}",
+			crate::consts::CREATE_ACK_CREATE_RUNTIME_HOOKS,
+			sig_buffer[0]
+		);
+	}
+
+	let mut size_buffer = [0u8; std::mem::size_of::<usize>()];
+	init_pipe
+		.read_exact(&mut size_buffer)
+		.expect("Could not read message size from init-pipe!");
+	let mut hook_state_buffer = vec![0; usize::from_le_bytes(size_buffer)];
+	init_pipe
+		.read_exact(&mut hook_state_buffer)
+		.expect("Could not read container state from init pipe!");
+	let mut hook_state: serde_json::Value =
+		serde_json::from_slice(&hook_state_buffer).expect("Could not parse container state!");
+
 	nix::unistd::chdir(rootfs_path).unwrap_or_else(|_| {
 		panic!(
 			"Could not change directory to rootfs path {:?}",
@@ -383,7 +463,16 @@ fn init_stage_child(args: SetupArgs) -> ! {
 		)
 	});
 
-	//TODO: Run create hooks
+	// The seccomp agent is only reachable through the runtime mount namespace
+	let seccomp_listener = linux_spec
+		.seccomp()
+		.as_ref()
+		.and_then(seccomp::connect_listener);
+
+	// The createContainer hooks are resolved in the runtime namespace, but run in the container namespaces
+	if let Some(hooks) = args.config.spec.hooks() {
+		hooks::run_hooks("createContainer", hooks.create_container(), &hook_state);
+	}
 
 	if args.config.cloneflags.contains(CloneFlags::CLONE_NEWNS) {
 		rootfs::pivot_root(rootfs_path);
@@ -392,27 +481,9 @@ fn init_stage_child(args: SetupArgs) -> ! {
 		nix::unistd::chdir("/").expect("Could not chdir to / after chroot!");
 	}
 
-	let user_port: u16 = env::var("RUNH_USER_PORT")
-		.unwrap_or_else(|_| "9975".to_string())
-		.parse()
-		.expect("RUNH_USER_PORT was not an unsigned integer!");
-
-	debug!(
-		"Hermit container: {},  Container port: {}",
-		args.config.is_hermit_container, user_port
-	);
-	let hermit_network_config = if args.config.is_hermit_container && user_port == 0 {
-		match tokio_runtime.block_on(network::create_tap()) {
-			Ok(config) => NetworkConfig::TapNetwork(config),
-			Err(err) => {
-				warn!("Hermit network setup could not be completed: {err}");
-				NetworkConfig::None
-			}
-		}
-	} else if args.config.is_hermit_container && user_port > 0 {
-		NetworkConfig::UserNetwork(user_port)
-	} else {
-		NetworkConfig::None
+	let hermit_network_config = match args.config.vm_config.as_ref().zip(hypervisor.as_ref()) {
+		Some((vm_config, hypervisor)) => hypervisor.create_network(vm_config, &tokio_runtime),
+		None => NetworkConfig::None,
 	};
 
 	//TODO: re-open /dev/null in the container if any std-fd points to it
@@ -449,7 +520,7 @@ fn init_stage_child(args: SetupArgs) -> ! {
 				ws_ypixel: 0,
 			});
 
-		console::setup_console(console_socket, win_size.as_ref());
+		console::setup_console(console_socket, win_size.as_ref(), true);
 	}
 
 	//Finalize rootfs
@@ -501,17 +572,13 @@ fn init_stage_child(args: SetupArgs) -> ! {
 		}
 	}
 
-	//TODO: Apply seccomp
 	//TODO: Finalize Namespace
 	// - Ensure all fd's are CLOEXEC
-	// - Change to cwd
-	// - Change user
-	// - Apply capabilities
 
 	//Verify the args[0] executable exists
 	let mut tap_fd = None;
 
-	let exec_args = if args.config.is_hermit_container {
+	let exec_args = if let Some(hypervisor) = &hypervisor {
 		let app = args
 			.config
 			.spec
@@ -524,17 +591,6 @@ fn init_stage_child(args: SetupArgs) -> ! {
 			.first()
 			.expect("Container spec does not contain any args!")
 			.as_str();
-		let app_root = Path::new(app)
-			.parent()
-			.expect("App path does not have a parent!")
-			.to_owned();
-		let kernel_path = app_root.join("hermit-loader");
-		let kernel = kernel_path.as_os_str().to_str().unwrap();
-		let micro_vm: u32 = env::var("RUNH_MICRO_VM")
-			.unwrap_or_else(|_| "0".to_string())
-			.parse()
-			.expect("RUNH_MICRO_VM was not an unsigned integer!");
-
 		tap_fd = if let NetworkConfig::TapNetwork(ref netconf) = hermit_network_config {
 			let tap_file = OpenOptions::new()
 				.read(true)
@@ -547,10 +603,8 @@ fn init_stage_child(args: SetupArgs) -> ! {
 		};
 
 		debug!("Network configuration {hermit_network_config:?}");
-		hermit::get_qemu_args(
-			kernel,
+		hypervisor.get_args(
 			app,
-			&hermit_network_config,
 			args.config
 				.spec
 				.process()
@@ -559,9 +613,9 @@ fn init_stage_child(args: SetupArgs) -> ! {
 				.args()
 				.as_ref()
 				.unwrap(),
-			micro_vm > 0,
-			std::fs::metadata("/dev/kvm").is_ok(),
-			&tap_fd.as_ref().map(AsRawFd::as_raw_fd),
+			&hermit_network_config,
+			args.config.vm_config.as_ref().unwrap(),
+			tap_fd.as_ref().map(AsRawFd::as_raw_fd),
 		)
 	} else {
 		args.config
@@ -613,45 +667,13 @@ fn init_stage_child(args: SetupArgs) -> ! {
 	nix::unistd::close(fifo_fd).expect("Could not close exec fifo O_PATH fd!");
 	nix::unistd::close(init_pipe.into_raw_fd()).expect("Could not close init pipe fd!");
 
-	if args.config.is_hermit_container {
-		let micro_vm: u32 = env::var("RUNH_MICRO_VM")
-			.unwrap_or_else(|_| "0".to_string())
-			.parse()
-			.expect("RUNH_MICRO_VM was not an unsigned integer!");
-
-		if micro_vm == 0 {
-			let virtiofsd_args: Vec<&str> = vec![
-				"virtiofsd",
-				"--socket-path=/run/vhostqemu",
-				"--shared-dir",
-				"/root",
-				"--sandbox",
-				"none",
-				"--seccomp",
-				"none",
-				"--inode-file-handles=never",
-			];
-
-			info!("Initialize virtiofsd: {}", virtiofsd_args.join(" "));
-
-			let virtiofsd_path_rel = Path::new(
-				virtiofsd_args
-					.first()
-					.expect("Container spec does not contain any args!"),
-			);
-			let virtiofsd_path_abs = paths::find_in_path(virtiofsd_path_rel, None)
-				.expect("Could not determine location of args-executable!");
-
-			let mut cmd = std::process::Command::new(virtiofsd_path_abs);
-			cmd.arg0(virtiofsd_args.first().unwrap());
-			if virtiofsd_args.len() > 1 {
-				cmd.args(virtiofsd_args.get(1..).unwrap());
-			}
-			cmd.envs(std::env::vars());
+	if let Some(hooks) = args.config.spec.hooks() {
+		hook_state["status"] = "created".into();
+		hooks::run_hooks("startContainer", hooks.start_container(), &hook_state);
+	}
 
-			#[allow(clippy::zombie_processes)]
-			let _child = cmd.spawn().expect("Unable to virtiofsd");
-		}
+	if let Some(hypervisor) = &hypervisor {
+		hypervisor.spawn_helpers(args.config.vm_config.as_ref().unwrap());
 	}
 
 	let mut cmd = std::process::Command::new(exec_path_abs);
@@ -661,9 +683,60 @@ fn init_stage_child(args: SetupArgs) -> ! {
 	}
 	cmd.envs(std::env::vars());
 
+	// Without no_new_privileges, loading the filter requires CAP_SYS_ADMIN, which the process
+	// may lose with its capabilities. Otherwise, the filter is loaded as late as possible.
+	let no_new_privileges = args
+		.config
+		.spec
+		.process()
+		.as_ref()
+		.and_then(|process| process.no_new_privileges())
+		.unwrap_or(false);
+	let mut seccomp_listener = seccomp_listener;
+	if !no_new_privileges {
+		if let Some(seccomp) = linux_spec.seccomp() {
+			seccomp::load_filter(seccomp, seccomp_listener.take(), &hook_state);
+		}
+	}
+
+	// Switch to the requested user. Capabilities have to survive the change of the user ID to
+	// apply the requested sets afterwards.
+	let process = args.config.spec.process().as_ref().unwrap();
+	if let Some(rlimits) = process.rlimits() {
+		rlimits::set_rlimits(rlimits);
+	}
+	if let Some(capabilities) = process.capabilities() {
+		capabilities::drop_bounding_set(capabilities);
+		prctl::set_keepcaps(true).expect("Could not set keepcaps flag!");
+	}
+	user::switch_user(process.user());
+	if let Some(capabilities) = process.capabilities() {
+		capabilities::set_capabilities(capabilities);
+	}
+
+	// Change to the cwd as the requested user, so that its permissions apply
+	let cwd = process.cwd();
+	if !cwd.as_os_str().is_empty() {
+		nix::unistd::chdir(cwd)
+			.unwrap_or_else(|err| panic!("Could not change directory to cwd {:?}: {}", cwd, err));
+	}
+
+	if no_new_privileges {
+		if let Some(seccomp) = linux_spec.seccomp() {
+			seccomp::load_filter(seccomp, seccomp_listener.take(), &hook_state);
+		}
+	}
+
 	if let Some(tap_fd) = tap_fd {
 		cmd.preserved_fds(vec![tap_fd]);
 	}
+	if let Some(hypervisor) = &hypervisor {
+		supervisor::supervise_vm(
+			cmd,
+			hypervisor.as_ref(),
+			args.config.vm_config.as_ref().unwrap(),
+		);
+	}
 	// use implicitly execvp => on success, this function doesn't return
 	let error = cmd.exec();
 
@@ -1,23 +1,56 @@
+use nix::sys::signal::Signal;
 use nix::unistd::Pid;
+use std::fs::OpenOptions;
+use std::io::BufReader;
 use std::{convert::TryFrom, path::PathBuf, str::FromStr};
 
-use crate::state;
+use crate::container::OCIContainer;
+use crate::{cgroups, state};
+
+/// Determines all processes of the container. Without a cgroup, all processes in the PID
+/// namespace of the container process are collected instead.
+pub fn get_container_processes(container: &OCIContainer, pid: Option<i32>) -> Vec<i32> {
+	if let Some(cgroup_path) = container.cgroup_path() {
+		return cgroups::get_processes(cgroup_path);
+	}
+
+	let Some(pid_ns) = pid.and_then(|pid| std::fs::read_link(format!("/proc/{pid}/ns/pid")).ok())
+	else {
+		return Vec::new();
+	};
+	if std::fs::read_link("/proc/self/ns/pid").is_ok_and(|own_ns| own_ns == pid_ns) {
+		warn!(
+			"Container shares the PID namespace with runh, only the container process is signaled!"
+		);
+		return pid.into_iter().collect();
+	}
+
+	procfs::process::all_processes()
+		.expect("Could not list processes!")
+		.flatten()
+		.filter(|process| {
+			std::fs::read_link(format!("/proc/{}/ns/pid", process.pid)).is_ok_and(|ns| ns == pid_ns)
+		})
+		.map(|process| process.pid)
+		.collect()
+}
 
 pub fn kill_container(project_dir: PathBuf, id: &str, sig: &str, all: bool) {
-	let container_state = if let Some(state) = state::get_container_state(project_dir, id) {
+	let container_state = if let Some(state) = state::get_container_state(project_dir.clone(), id) {
 		state
 	} else {
 		warn!("Could not query state for container {id}");
 		return;
 	};
 
-	if container_state.status == "stopped" {
+	if container_state.status == "stopped" && !all {
 		// container is already stopped => nothing to do
 		return;
-	} else if all {
-		warn!("Sending signals to all container processes is currently unimplemented!");
-		return;
-	} else if container_state.status != "created" && container_state.status != "running" {
+	} else if container_state.status != "created"
+		&& container_state.status != "running"
+		&& container_state.status != "paused"
+		&& container_state.status != "stopped"
+	{
 		warn!(
 			"Cannot send signals to container with state \"{}\"!",
 			container_state.status
@@ -25,20 +58,56 @@ pub fn kill_container(project_dir: PathBuf, id: &str, sig: &str, all: bool) {
 		return;
 	}
 
-	let pid = container_state.pid.unwrap();
 	let signal = if let Ok(sig_nr) = sig.parse::<i32>() {
-		nix::sys::signal::Signal::try_from(sig_nr)
-			.unwrap_or_else(|_| panic!("Could not parse signal number {}", sig))
+		Signal::try_from(sig_nr).unwrap_or_else(|_| panic!("Could not parse signal number {}", sig))
 	} else {
 		let signal_str = if !sig.starts_with("SIG") {
 			format!("SIG{sig}")
 		} else {
 			sig.to_owned()
 		};
-		nix::sys::signal::Signal::from_str(signal_str.as_str())
+		Signal::from_str(signal_str.as_str())
 			.unwrap_or_else(|_| panic!("Could not parse signal string {}", sig))
 	};
 
+	if all {
+		// The processes of a stopped container might still be alive, e.g. helpers like virtiofsd
+		let container_file = OpenOptions::new()
+			.read(true)
+			.open(project_dir.join(id).join("container.json"))
+			.expect("Could not open container file!");
+		let container: OCIContainer = serde_json::from_reader(BufReader::new(container_file))
+			.expect("Could not parse container file!");
+
+		let processes = get_container_processes(&container, container_state.pid);
+		if container_state.status == "stopped" && processes.is_empty() {
+			warn!("Container {id} is stopped and has no remaining processes to signal!");
+			return;
+		}
+
+		// Freeze the cgroup, so that no process is able to fork off children, which are not
+		// signaled. A paused container stays frozen afterwards.
+		let frozen_cgroup = container
+			.cgroup_path()
+			.as_deref()
+			.filter(|cgroup_path| cgroup_path.exists() && !cgroups::is_frozen(cgroup_path));
+		if let Some(cgroup_path) = frozen_cgroup {
+			cgroups::freeze(cgroup_path, true);
+		}
+
+		for pid in get_container_processes(&container, container_state.pid) {
+			debug!("Send signal {signal} to container process {pid}");
+			nix::sys::signal::kill(Pid::from_raw(pid), signal)
+				.unwrap_or_else(|_| warn!("Could not send signal {sig} to process ID {pid}!"));
+		}
+
+		if let Some(cgroup_path) = frozen_cgroup {
+			cgroups::freeze(cgroup_path, false);
+		}
+		return;
+	}
+
+	let pid = container_state.pid.unwrap();
 	nix::sys::signal::kill(Pid::from_raw(pid), signal)
 		.unwrap_or_else(|_| warn!("Could not send signal {sig} to container process ID  {pid}!"));
 }
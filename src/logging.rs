@@ -6,11 +6,14 @@ use serde_json::to_string;
 use std::fmt;
 use std::fs::File;
 use std::fs::OpenOptions;
-use std::io::Write;
+use std::io::{BufRead, Write};
+use std::os::fd::OwnedFd;
 use std::os::unix::prelude::FromRawFd;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::string::String;
 use std::sync::Mutex;
+use std::thread::JoinHandle;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
@@ -186,3 +189,27 @@ pub fn init(
 
 	debug!("Runh logger initialized!");
 }
+
+/// Spawns a thread, which reads the JSON log entries written by `runh init`
+/// to the log pipe and passes them on to our own logger.
+pub fn spawn_log_forwarder(parent_log_fd: OwnedFd) -> JoinHandle<()> {
+	std::thread::spawn(move || {
+		let log_pipe = File::from(parent_log_fd);
+		let mut reader = std::io::BufReader::new(log_pipe);
+		let mut buffer: Vec<u8> = vec![];
+		while let Ok(bytes_read) = reader.read_until(b"}"[0], &mut buffer) {
+			if bytes_read > 0 {
+				if let Ok(log_entry) = serde_json::from_slice::<LogEntry>(buffer.as_slice()) {
+					match log::Level::from_str(log_entry.level.as_str()) {
+						Ok(level) => log!(level, "[INIT] {}", log_entry.msg),
+						Err(_) => info!("[INIT] {}", log_entry.msg),
+					}
+					buffer.clear();
+				}
+			} else {
+				debug!("Read zero bytes from log pipe, closing forwarder...");
+				break;
+			}
+		}
+	})
+}
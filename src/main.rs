@@ -3,16 +3,21 @@ extern crate colour;
 #[macro_use]
 extern crate log;
 
+mod capabilities;
+mod cgroups;
 mod container;
 mod create;
 mod delete;
 mod kill;
-//mod exec;
 mod console;
 mod consts;
 mod devices;
+mod exec;
+mod firecracker;
 mod flags;
 mod hermit;
+mod hooks;
+mod hypervisor;
 mod init;
 mod list;
 mod logging;
@@ -20,21 +25,40 @@ mod mounts;
 mod namespaces;
 mod network;
 mod paths;
+mod pause;
+mod qemu;
+mod qmp;
+mod rlimits;
 mod rootfs;
+mod rootless;
+mod run;
+mod seccomp;
+mod signals;
 mod spec;
 mod start;
 mod state;
+mod supervisor;
+mod syscalls;
+mod systemd;
+mod uhyve;
+mod user;
+mod userns;
+mod vm;
+mod vmconfig;
 
 use crate::create::*;
 use crate::delete::*;
-//use crate::exec::*;
+use crate::exec::*;
 use crate::init::*;
 use crate::kill::*;
 use crate::list::*;
 use crate::logging::*;
+use crate::pause::*;
+use crate::run::*;
 use crate::spec::*;
 use crate::start::*;
 use crate::state::*;
+use crate::vm::*;
 use clap::{crate_version, Parser, Subcommand};
 use serde::Deserialize;
 use std::fs::{read_to_string, DirBuilder};
@@ -52,6 +76,18 @@ struct Config {
 	smp: Option<u32>,
 	/// define the memory size (in MiB), which the VM should use
 	memory_size: Option<u64>,
+	/// defines the memory (in MiB), which is reserved for QEMU itself, if the VM size is derived
+	/// from the memory limit of the container
+	qemu_memory_overhead: Option<u64>,
+	/// defines the hypervisor (qemu, uhyve or firecracker), which runs Hermit applications
+	hypervisor: Option<hypervisor::HypervisorKind>,
+	/// defines the time (in seconds), which a Hermit application gets to shut down after SIGTERM
+	shutdown_grace_period: Option<u64>,
+	/// specifies if QEMU is restricted to the system calls it requires (enabled by default)
+	qemu_seccomp: Option<bool>,
+	/// specifies if QEMU additionally sandboxes itself, which requires QEMU to be built with
+	/// libseccomp (disabled by default)
+	qemu_sandbox: Option<bool>,
 }
 
 impl Config {
@@ -60,6 +96,11 @@ impl Config {
 			kvm: None,
 			smp: None,
 			memory_size: None,
+			qemu_memory_overhead: None,
+			hypervisor: None,
+			shutdown_grace_period: None,
+			qemu_seccomp: None,
+			qemu_sandbox: None,
 		}
 	}
 }
@@ -132,6 +173,8 @@ fn parse_matches(cli: &Cli) {
 			console_socket.clone(),
 			cli.debug_config,
 			cli.log_level,
+			false,
+			cli.systemd_cgroup,
 		),
 		Commands::Delete {
 			container_id,
@@ -142,7 +185,57 @@ fn parse_matches(cli: &Cli) {
 			signal,
 			all,
 		} => kill_container(project_dir.clone(), container_id, signal, *all),
+		Commands::Exec {
+			container_id,
+			command,
+			command_options,
+			process,
+			tty,
+			console_socket,
+			env,
+			cwd,
+			user,
+			detach,
+			pid_file,
+		} => exec_container(
+			project_dir.clone(),
+			container_id,
+			process.clone(),
+			command.clone(),
+			command_options,
+			*tty,
+			console_socket.clone(),
+			env,
+			cwd.clone(),
+			user.clone(),
+			*detach,
+			pid_file.clone(),
+			cli.log_level,
+		),
 		Commands::Start { container_id } => start_container(project_dir.clone(), container_id),
+		Commands::Run {
+			container_id,
+			bundle,
+			pid_file,
+			console_socket,
+			detach,
+		} => run_container(
+			project_dir.clone(),
+			container_id,
+			bundle.clone(),
+			pid_file.clone(),
+			console_socket.clone(),
+			*detach,
+			cli.debug_config,
+			cli.log_level,
+			cli.systemd_cgroup,
+		),
+		Commands::Pause { container_id } => pause_container(project_dir.clone(), container_id),
+		Commands::Resume { container_id } => resume_container(project_dir.clone(), container_id),
+		Commands::Vm {
+			container_id,
+			command,
+		} => vm_command(project_dir.clone(), container_id, command),
 		Commands::List => list_containers(project_dir.clone()),
 		Commands::Init => init_container(),
 		_ => {
@@ -211,15 +304,76 @@ enum Commands {
 		/// Id of the container
 		container_id: String,
 		/// Command, which will be executed in the container
-		command: String,
+		#[arg(required_unless_present = "process")]
+		command: Option<String>,
 		/// Arguments of the command
+		#[arg(trailing_var_arg = true, allow_hyphen_values = true)]
 		command_options: Vec<String>,
+		/// Path to a process.json describing the process. All other process options are ignored.
+		#[arg(short = 'p', long)]
+		process: Option<PathBuf>,
+		/// Allocate a pseudo-TTY
+		#[arg(short = 't', long, default_value_t)]
+		tty: bool,
+		/// Path to an AF_UNIX socket for console IO
+		#[arg(long)]
+		console_socket: Option<PathBuf>,
+		/// Set environment variables (KEY=VALUE)
+		#[arg(short = 'e', long)]
+		env: Vec<String>,
+		/// Current working directory in the container
+		#[arg(long)]
+		cwd: Option<PathBuf>,
+		/// User ID and optionally group ID to run the process as (<uid>[:<gid>])
+		#[arg(short = 'u', long)]
+		user: Option<String>,
+		/// Detach from the process and return after it has been started
+		#[arg(short = 'd', long, default_value_t)]
+		detach: bool,
+		/// File to write the process id to
+		#[arg(long)]
+		pid_file: Option<PathBuf>,
 	},
 	/// Executes the user defined process in a created container
 	Start {
 		/// Id of the container
 		container_id: String,
 	},
+	/// Create and start a container. Unless detached, runh stays attached to the container
+	/// and returns its exit code.
+	Run {
+		/// Id of the container
+		container_id: String,
+		/// path to the root of the bundle directory
+		#[arg(short = 'b', long)]
+		bundle: PathBuf,
+		/// File to write the process id to
+		#[arg(long)]
+		pid_file: Option<PathBuf>,
+		/// Path to an AF_UNIX socket for console IO (only used when detached)
+		#[arg(long)]
+		console_socket: Option<PathBuf>,
+		/// Detach from the container after it has been started
+		#[arg(short = 'd', long, default_value_t)]
+		detach: bool,
+	},
+	/// Suspend all processes of a running container (or the vCPUs of a Hermit VM)
+	Pause {
+		/// Id of the container
+		container_id: String,
+	},
+	/// Resume a previously paused container
+	Resume {
+		/// Id of the container
+		container_id: String,
+	},
+	/// Control the QEMU VM of a running Hermit container through QMP
+	Vm {
+		/// Id of the container
+		container_id: String,
+		#[command(subcommand)]
+		command: VmCommand,
+	},
 	/// Lists containers started by runh with the given root
 	List,
 	/// Init process running inside a newly created container. Do not use outside of runh!
@@ -235,8 +389,8 @@ enum Commands {
 #[command(next_line_help = true)]
 #[command(propagate_version = true)]
 struct Cli {
-	/// root directory for storage of vm state
-	#[arg(long, default_value = "/run/user/1000/runh", value_name = "ROOT")]
+	/// root directory for storage of vm state (defaults to $XDG_RUNTIME_DIR/runh for rootless runh)
+	#[arg(long, default_value_os_t = rootless::get_default_root(), value_name = "ROOT")]
 	root: PathBuf,
 
 	/// The logging level of the application
@@ -259,7 +413,8 @@ struct Cli {
 	#[arg(long, default_value_t)]
 	debug_config: bool,
 
-	/// Currently unimplemented!
+	/// Use systemd transient scope units to manage the cgroups of containers.
+	/// The cgroupsPath of a container has to be given as slice:prefix:name.
 	#[arg(long)]
 	systemd_cgroup: bool,
 
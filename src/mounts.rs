@@ -10,7 +10,7 @@ use std::{
 	path::{Path, PathBuf},
 };
 
-use crate::rootfs;
+use crate::{cgroups, rootfs};
 
 #[derive(Clone)]
 pub struct MountOptions {
@@ -236,19 +236,50 @@ pub fn configure_mounts(
 							);
 						}
 					}
-					Some("cgroup") => {
-						//TODO: Additional checks for cGroup v1 vs v2,
-						//		mount might fail when the cgroup-NS was not unshared earlier
-						// create_all_dirs(&destination_resolved);
-						// mount_with_flags(
-						// 	"cgroup2",
-						// 	&mount_src,
-						// 	&mount_dest,
-						// 	&destination_resolved,
-						// 	mount_options.clone(),
-						// 	mount_label.as_ref(),
-						// );
-						warn!("Warning: cgroups are currently unimplemented!");
+					Some("cgroup") | Some("cgroup2") => {
+						if !cgroups::is_cgroup2_available() {
+							warn!("Only cgroup v2 is supported by runh. Skipping cgroup mount at {:?}!", mount_dest);
+							continue;
+						}
+						create_all_dirs(&destination_resolved);
+
+						// Inside a new cgroup namespace, we are located at the root of the hierarchy and
+						// can safely mount cgroup2. Otherwise, only our own cgroup is bind-mounted.
+						let own_cgroup = cgroups::get_process_cgroup(std::process::id() as i32)
+							.expect("Could not determine own cgroup!");
+						if own_cgroup == Path::new(cgroups::CGROUP_ROOT) {
+							mount_with_flags(
+								"cgroup2",
+								&mount_src,
+								mount_dest,
+								&destination_resolved,
+								mount_options,
+								mount_label.as_ref(),
+							);
+						} else {
+							let is_read_only =
+								mount_options.mount_flags.contains(MsFlags::MS_RDONLY);
+							let mut bind_options = mount_options;
+							bind_options.mount_flags.insert(MsFlags::MS_BIND);
+							bind_options.mount_flags.insert(MsFlags::MS_REC);
+							mount_with_flags(
+								"bind",
+								&own_cgroup,
+								mount_dest,
+								&destination_resolved,
+								bind_options.clone(),
+								mount_label.as_ref(),
+							);
+							if is_read_only {
+								remount(
+									"bind",
+									&own_cgroup,
+									mount_dest,
+									&destination_resolved,
+									bind_options,
+								);
+							}
+						}
 					}
 					_ => {
 						if destination_resolved.starts_with(rootfs.join("proc")) {
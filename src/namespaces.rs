@@ -33,3 +33,36 @@ pub fn join_namespaces(namespaces: &[runtime::LinuxNamespace]) {
 			.unwrap_or_else(|_| panic!("Failed to join NS {:?}", ns_config.1));
 	}
 }
+
+fn get_proc_name(typ: runtime::LinuxNamespaceType) -> &'static str {
+	match typ {
+		runtime::LinuxNamespaceType::Cgroup => "cgroup",
+		runtime::LinuxNamespaceType::Ipc => "ipc",
+		runtime::LinuxNamespaceType::Mount => "mnt",
+		runtime::LinuxNamespaceType::Network => "net",
+		runtime::LinuxNamespaceType::Pid => "pid",
+		runtime::LinuxNamespaceType::User => "user",
+		runtime::LinuxNamespaceType::Uts => "uts",
+		runtime::LinuxNamespaceType::Time => "time",
+	}
+}
+
+/// Returns the namespaces of the given types, which are currently used by the process `pid`.
+/// The user namespace is always placed first, as it has to be joined before all others.
+pub fn get_process_namespaces(
+	pid: i32,
+	namespaces: &[runtime::LinuxNamespace],
+) -> Vec<runtime::LinuxNamespace> {
+	let mut result: Vec<runtime::LinuxNamespace> = namespaces
+		.iter()
+		.map(|ns| {
+			runtime::LinuxNamespaceBuilder::default()
+				.typ(ns.typ())
+				.path(format!("/proc/{}/ns/{}", pid, get_proc_name(ns.typ())))
+				.build()
+				.unwrap()
+		})
+		.collect();
+	result.sort_by_key(|ns| ns.typ() != runtime::LinuxNamespaceType::User);
+	result
+}
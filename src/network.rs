@@ -7,12 +7,16 @@ use nix::sys::stat::SFlag;
 use rtnetlink::Error::NetlinkError;
 use std::net::IpAddr;
 use std::num::NonZeroI32;
+use std::os::unix::io::AsRawFd;
 use std::path::PathBuf;
 use std::{error::Error, fmt, net::Ipv4Addr};
 
 // FIXME: https://github.com/rust-netlink/netlink-packet-route/issues/88
 const MACVLAN_MODE_PASSTHRU: u32 = 8;
 
+nix::ioctl_write_ptr_bad!(ioctl_tunsetiff, libc::TUNSETIFF, libc::ifreq);
+nix::ioctl_write_int_bad!(ioctl_tunsetpersist, libc::TUNSETPERSIST);
+
 #[derive(Debug)]
 struct VirtioNetworkError {
 	details: String,
@@ -27,6 +31,16 @@ pub struct VirtioNetworkConfig {
 	pub macvtap_index: u32,
 }
 
+/// Network configuration of a tap device, which is connected to eth0 through traffic redirection
+#[derive(Debug)]
+pub struct TapDeviceConfig {
+	pub ip: Ipv4Addr,
+	pub gateway: Ipv4Addr,
+	pub mask: Ipv4Addr,
+	pub mac: String,
+	pub name: String,
+}
+
 impl From<String> for VirtioNetworkError {
 	fn from(msg: String) -> Self {
 		VirtioNetworkError { details: msg }
@@ -58,41 +72,37 @@ pub async fn set_lo_up() -> Result<(), rtnetlink::Error> {
 	Ok(())
 }
 
-/**
- This function is in large parts inspired by the runnc code for Nabla Containers
- https://github.com/nabla-containers/runnc/blob/46ededdd75a03cecf05936a1a45d5d0096a2b117/nabla-lib/network/network_linux.go
-*/
-pub async fn create_tap() -> Result<VirtioNetworkConfig, Box<dyn std::error::Error>> {
-	let (connection, handle, _) = rtnetlink::new_connection()?;
-	tokio::spawn(connection);
+struct Eth0Config {
+	index: u32,
+	ip: Ipv4Addr,
+	prefix_length: u8,
+	gateway: Ipv4Addr,
+	mac: Option<String>,
+}
 
-	// Check for an existing tap device
-	let mut tap_link_req = handle
-		.link()
-		.get()
-		.match_name("macvtap0".to_string())
-		.execute();
+impl Eth0Config {
+	fn mask(&self) -> Ipv4Addr {
+		Ipv4Addr::from(0xffffffffu32 << self.prefix_length)
+	}
+}
 
-	let do_init = match tap_link_req.try_next().await {
-		Ok(Some(_)) => {
-			warn!("Tap device already exists in current network namespace. Trying to read configuration from eth0 / macvtap0 device...");
-			false
-		}
-		Ok(None) => {
-			warn!("Tap device exists in namespace but cannot be read. Trying to re-do setup...");
-			true
-		}
-		Err(NetlinkError(ErrorMessage { code, .. })) if code == NonZeroI32::new(-libc::ENODEV) => {
-			// This is the expected case that is triggered when the tap device does not exist in the current namespace
-			true
-		}
-		Err(err) => {
-			return Err(Box::new(VirtioNetworkError::from(format!(
-				"Macvtap0 interface detection failed: {err}"
-			))));
-		}
-	};
+fn format_mac(addr: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+	if addr.len() != 6 {
+		return Err(Box::new(VirtioNetworkError::from(format!(
+			"Received invalid MAC address {addr:?}!"
+		))));
+	}
+	Ok(format!(
+		"{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+		addr[0], addr[1], addr[2], addr[3], addr[4], addr[5]
+	))
+}
 
+/// Reads the configuration of the eth0 device, which the container engine created in the
+/// network namespace of the container.
+async fn get_eth0_config(
+	handle: &rtnetlink::Handle,
+) -> Result<Eth0Config, Box<dyn std::error::Error>> {
 	// Get link info for eth0 device
 	let link_info = handle
 		.link()
@@ -106,8 +116,15 @@ pub async fn create_tap() -> Result<VirtioNetworkConfig, Box<dyn std::error::Err
 	// Extract device index from link info
 	let eth0_device_index = link_info.header.index;
 
-	//Setup network parameters
 	let mut mac_address: Option<String> = None;
+	for link_attribute in link_info.attributes.into_iter() {
+		if let LinkAttribute::Address(addr) = link_attribute {
+			mac_address = Some(format_mac(&addr)?);
+			break;
+		}
+	}
+
+	//Setup network parameters
 	let mut ip_address: Option<Ipv4Addr> = None;
 	let mut prefix_length: Option<u8> = None;
 	let mut gateway_address: Option<Ipv4Addr> = None;
@@ -147,12 +164,61 @@ pub async fn create_tap() -> Result<VirtioNetworkConfig, Box<dyn std::error::Err
 		}
 	}
 
+	Ok(Eth0Config {
+		index: eth0_device_index,
+		ip: ip_address.expect("IP address could not be determined during networking setup!"),
+		prefix_length: prefix_length
+			.expect("IP prefix length could not be determined during networking setup!"),
+		gateway: gateway_address
+			.expect("Gateway address could not be determined during networking setup!"),
+		mac: mac_address,
+	})
+}
+
+/**
+ This function is in large parts inspired by the runnc code for Nabla Containers
+ https://github.com/nabla-containers/runnc/blob/46ededdd75a03cecf05936a1a45d5d0096a2b117/nabla-lib/network/network_linux.go
+*/
+pub async fn create_tap() -> Result<VirtioNetworkConfig, Box<dyn std::error::Error>> {
+	let (connection, handle, _) = rtnetlink::new_connection()?;
+	tokio::spawn(connection);
+
+	// Check for an existing tap device
+	let mut tap_link_req = handle
+		.link()
+		.get()
+		.match_name("macvtap0".to_string())
+		.execute();
+
+	let do_init = match tap_link_req.try_next().await {
+		Ok(Some(_)) => {
+			warn!("Tap device already exists in current network namespace. Trying to read configuration from eth0 / macvtap0 device...");
+			false
+		}
+		Ok(None) => {
+			warn!("Tap device exists in namespace but cannot be read. Trying to re-do setup...");
+			true
+		}
+		Err(NetlinkError(ErrorMessage { code, .. })) if code == NonZeroI32::new(-libc::ENODEV) => {
+			// This is the expected case that is triggered when the tap device does not exist in the current namespace
+			true
+		}
+		Err(err) => {
+			return Err(Box::new(VirtioNetworkError::from(format!(
+				"Macvtap0 interface detection failed: {err}"
+			))));
+		}
+	};
+
+	let eth0 = get_eth0_config(&handle).await?;
+	let mut mac_address: Option<String> = None;
+
 	if do_init {
 		// Create macvtap0 interface
 		handle
 			.link()
 			.add()
-			.macvtap("macvtap0".into(), eth0_device_index, MACVLAN_MODE_PASSTHRU)
+			.macvtap("macvtap0".into(), eth0.index, MACVLAN_MODE_PASSTHRU)
 			.execute()
 			.await?;
 	}
@@ -172,15 +238,7 @@ pub async fn create_tap() -> Result<VirtioNetworkConfig, Box<dyn std::error::Err
 	// Extract mac from macvtap
 	for link_attribute in macvtap_link_info.attributes.into_iter() {
 		if let LinkAttribute::Address(addr) = link_attribute {
-			if addr.len() != 6 {
-				return Err(Box::new(VirtioNetworkError::from(format!(
-					"Received invalid MAC address {addr:?} for macvtap device!"
-				))));
-			}
-			mac_address = Some(format!(
-				"{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-				addr[0], addr[1], addr[2], addr[3], addr[4], addr[5]
-			));
+			mac_address = Some(format_mac(&addr)?);
 			debug!(
 				"Found macvtap mac address: {}",
 				mac_address.as_ref().unwrap()
@@ -211,27 +269,101 @@ pub async fn create_tap() -> Result<VirtioNetworkConfig, Box<dyn std::error::Err
 	)
 	.expect("Could not create tap device corresponding to macvtap0!");
 
-	// Assume all network parameters have been set
-	let ip_address =
-		ip_address.expect("IP address could not be determined during networking setup!");
-	let prefix_length =
-		prefix_length.expect("IP prefix length could not be determined during networking setup!");
-	let gateway_address =
-		gateway_address.expect("Gateway address could not be determined during networking setup!");
 	let mac_address =
 		mac_address.expect("MAC address could not be determined during networking setup!");
 
 	info!(
 		"Found / created network setup: IP={},MASK={},GW={},MAC={}",
-		ip_address, prefix_length, gateway_address, mac_address
+		eth0.ip, eth0.prefix_length, eth0.gateway, mac_address
 	);
 
-	let mask: Ipv4Addr = Ipv4Addr::from(0xffffffffu32 << prefix_length);
 	Ok(VirtioNetworkConfig {
-		ip: ip_address,
-		gateway: gateway_address,
-		mask,
+		ip: eth0.ip,
+		gateway: eth0.gateway,
+		mask: eth0.mask(),
 		mac: mac_address,
 		macvtap_index,
 	})
 }
+
+/// Creates a persistent tap device with the given name.
+fn create_persistent_tap(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+	let tun = std::fs::OpenOptions::new()
+		.read(true)
+		.write(true)
+		.open("/dev/net/tun")?;
+
+	let mut ifreq: libc::ifreq = unsafe { std::mem::zeroed() };
+	for (dst, src) in ifreq.ifr_name.iter_mut().zip(name.bytes()) {
+		*dst = src as libc::c_char;
+	}
+	ifreq.ifr_ifru.ifru_flags = (libc::IFF_TAP | libc::IFF_NO_PI) as libc::c_short;
+
+	unsafe {
+		ioctl_tunsetiff(tun.as_raw_fd(), &ifreq)?;
+		ioctl_tunsetpersist(tun.as_raw_fd(), 1)?;
+	}
+	Ok(())
+}
+
+/// Redirects all packets arriving at the device `from` to the device `to`.
+async fn redirect_traffic(
+	handle: &rtnetlink::Handle,
+	from: u32,
+	to: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+	handle.qdisc().add(from as i32).ingress().execute().await?;
+	handle
+		.traffic_filter(from as i32)
+		.add()
+		.parent(0xffff0000)
+		.protocol((libc::ETH_P_ALL as u16).to_be())
+		.redirect(to)?
+		.execute()
+		.await?;
+	Ok(())
+}
+
+/// Creates a tap device for hypervisors, which are not able to use a macvtap device. The tap
+/// device is connected to eth0 by redirecting the traffic in both directions, so that the VM
+/// takes over the addresses of eth0 (analogous to the tc-redirect-tap CNI plugin).
+pub async fn create_redirect_tap(
+	name: &str,
+) -> Result<TapDeviceConfig, Box<dyn std::error::Error>> {
+	let (connection, handle, _) = rtnetlink::new_connection()?;
+	tokio::spawn(connection);
+
+	let eth0 = get_eth0_config(&handle).await?;
+	let mac_address = eth0
+		.mac
+		.clone()
+		.expect("MAC address could not be determined during networking setup!");
+
+	create_persistent_tap(name)?;
+	let tap_link_info = handle
+		.link()
+		.get()
+		.match_name(name.to_string())
+		.execute()
+		.try_next()
+		.await?
+		.unwrap_or_else(|| panic!("Could not read link info for interface {}!", name));
+	let tap_index = tap_link_info.header.index;
+	handle.link().set(tap_index).up().execute().await?;
+
+	redirect_traffic(&handle, eth0.index, tap_index).await?;
+	redirect_traffic(&handle, tap_index, eth0.index).await?;
+
+	info!(
+		"Created tap device {}: IP={},MASK={},GW={},MAC={}",
+		name, eth0.ip, eth0.prefix_length, eth0.gateway, mac_address
+	);
+
+	Ok(TapDeviceConfig {
+		ip: eth0.ip,
+		gateway: eth0.gateway,
+		mask: eth0.mask(),
+		mac: mac_address,
+		name: name.to_string(),
+	})
+}
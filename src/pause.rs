@@ -0,0 +1,68 @@
+use crate::container::OCIContainer;
+use crate::hypervisor::HypervisorKind;
+use crate::{cgroups, firecracker, qmp, state, vm};
+use std::fs::OpenOptions;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+fn set_container_paused(project_dir: PathBuf, id: &str, paused: bool) {
+	let container_state = state::get_container_state(project_dir.clone(), id)
+		.unwrap_or_else(|| panic!("Container {} does not exist!", id));
+	let expected_status = if paused { "running" } else { "paused" };
+	if container_state.status != expected_status {
+		panic!(
+			"Container is not {}, but has state \"{}\"!",
+			expected_status, container_state.status
+		);
+	}
+	let pid = container_state.pid.unwrap();
+
+	let container_dir = project_dir.join(id);
+	let container_file = OpenOptions::new()
+		.read(true)
+		.open(container_dir.join("container.json"))
+		.expect("Could not open container file!");
+	let container: OCIContainer = serde_json::from_reader(BufReader::new(container_file))
+		.expect("Could not parse container file!");
+
+	match container.hypervisor() {
+		Some(HypervisorKind::Qemu) => {
+			// Stop or continue the vCPUs of the VM, so that the unikernel is frozen as a whole
+			let mut connection =
+				qmp::QmpConnection::connect(&container_dir.join(vm::QMP_SOCKET_LINK))
+					.unwrap_or_else(|err| {
+						panic!("Could not connect to QMP socket of the VM: {}", err)
+					});
+			connection
+				.execute(if paused { "stop" } else { "cont" }, None)
+				.unwrap_or_else(|err| panic!("Could not change the run state of the VM: {}", err));
+		}
+		Some(HypervisorKind::Firecracker) => {
+			firecracker::set_paused(pid, paused)
+				.unwrap_or_else(|err| panic!("Could not change the run state of the VM: {}", err));
+		}
+		_ => {
+			let cgroup_path = container.cgroup_path().as_ref().expect(
+				"Container does not run in a dedicated cgroup and can therefore not be frozen!",
+			);
+			cgroups::freeze(cgroup_path, paused);
+		}
+	}
+
+	let paused_file = container_dir.join(state::PAUSED_FILE);
+	if paused {
+		std::fs::write(paused_file, "").expect("Could not record pause state!");
+	} else {
+		std::fs::remove_file(paused_file).expect("Could not record pause state!");
+	}
+}
+
+pub fn pause_container(project_dir: PathBuf, id: &str) {
+	set_container_paused(project_dir, id, true);
+	info!("Container {id} paused");
+}
+
+pub fn resume_container(project_dir: PathBuf, id: &str) {
+	set_container_paused(project_dir, id, false);
+	info!("Container {id} resumed");
+}
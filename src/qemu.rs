@@ -0,0 +1,623 @@
+use crate::hermit::{Architecture, NetworkConfig};
+use crate::hypervisor::Hypervisor;
+use crate::vmconfig::VmConfig;
+use crate::{devices, paths, qmp, seccomp};
+use oci_spec::runtime::{
+	Arch, LinuxSeccomp, LinuxSeccompAction, LinuxSeccompArgBuilder, LinuxSeccompBuilder,
+	LinuxSeccompOperator, LinuxSyscallBuilder,
+};
+use std::os::unix::io::RawFd;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+pub struct Qemu;
+
+/// Time, which QEMU gets to create the VM
+const START_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Memory (in MiB), which is reserved for QEMU itself if not configured otherwise
+const DEFAULT_MEMORY_OVERHEAD: u64 = 64;
+
+/// System calls, which QEMU requires to run a Hermit application with the command line of
+/// `get_qemu_args`. This includes KVM and TCG, the QMP socket, virtiofs through vhost-user as
+/// well as tap and user networking. `seccomp` is only required by `-sandbox`. The supervisor
+/// allows `execve` for the start of QEMU only. Processes are restricted by `get_seccomp_profile`.
+const QEMU_SYSCALLS: &[&str] = &[
+	// Process and thread management
+	"arch_prctl",
+	"exit",
+	"exit_group",
+	"futex",
+	"get_robust_list",
+	"getegid",
+	"geteuid",
+	"getgid",
+	"getpid",
+	"getppid",
+	"getresgid",
+	"getresuid",
+	"getrlimit",
+	"gettid",
+	"getuid",
+	"membarrier",
+	"prctl",
+	"prlimit64",
+	"restart_syscall",
+	"rseq",
+	"sched_getaffinity",
+	"sched_getparam",
+	"sched_getscheduler",
+	"sched_setaffinity",
+	"sched_yield",
+	"seccomp",
+	"set_robust_list",
+	"set_tid_address",
+	"setrlimit",
+	"wait4",
+	"waitid",
+	// Signals and timers
+	"clock_getres",
+	"clock_gettime",
+	"clock_nanosleep",
+	"gettimeofday",
+	"nanosleep",
+	"rt_sigaction",
+	"rt_sigprocmask",
+	"rt_sigreturn",
+	"rt_sigtimedwait",
+	"sigaltstack",
+	"signalfd4",
+	"timerfd_create",
+	"timerfd_gettime",
+	"timerfd_settime",
+	// Memory management of the guest memory
+	"brk",
+	"madvise",
+	"mbind",
+	"memfd_create",
+	"mlock",
+	"mlockall",
+	"mmap",
+	"mprotect",
+	"mremap",
+	"msync",
+	"munlock",
+	"munmap",
+	// Files, devices (KVM, tap, vhost-net) and the event loop
+	"access",
+	"close",
+	"close_range",
+	"dup",
+	"dup2",
+	"dup3",
+	"epoll_create1",
+	"epoll_ctl",
+	"epoll_pwait",
+	"epoll_wait",
+	"eventfd2",
+	"faccessat",
+	"faccessat2",
+	"fallocate",
+	"fcntl",
+	"fdatasync",
+	"fstat",
+	"fstatfs",
+	"fsync",
+	"ftruncate",
+	"getcwd",
+	"getdents64",
+	"getrandom",
+	"io_uring_enter",
+	"io_uring_register",
+	"io_uring_setup",
+	"ioctl",
+	"lseek",
+	"lstat",
+	"newfstatat",
+	"open",
+	"openat",
+	"pipe",
+	"pipe2",
+	"poll",
+	"ppoll",
+	"pread64",
+	"preadv",
+	"preadv2",
+	"pselect6",
+	"pwrite64",
+	"pwritev",
+	"pwritev2",
+	"read",
+	"readlink",
+	"readlinkat",
+	"readv",
+	"select",
+	"stat",
+	"statfs",
+	"statx",
+	"sysinfo",
+	"umask",
+	"uname",
+	"unlink",
+	"unlinkat",
+	"write",
+	"writev",
+	// QMP, vhost-user and user networking
+	"accept",
+	"accept4",
+	"bind",
+	"connect",
+	"getpeername",
+	"getsockname",
+	"getsockopt",
+	"listen",
+	"recvfrom",
+	"recvmmsg",
+	"recvmsg",
+	"sendmmsg",
+	"sendmsg",
+	"sendto",
+	"setsockopt",
+	"shutdown",
+	"socket",
+	"socketpair",
+];
+
+/// System calls, which send signals and are only allowed, if QEMU signals itself
+const QEMU_SIGNAL_SYSCALLS: &[&str] = &["kill", "tgkill"];
+
+/// Seccomp profile of QEMU, which is installed in addition to the profile of the container.
+fn get_seccomp_profile() -> LinuxSeccomp {
+	let to_names = |names: &[&str]| {
+		names
+			.iter()
+			.map(|name| name.to_string())
+			.collect::<Vec<_>>()
+	};
+	let allowed = LinuxSyscallBuilder::default()
+		.names(to_names(QEMU_SYSCALLS))
+		.action(LinuxSeccompAction::ScmpActAllow)
+		.build()
+		.unwrap();
+	let own_pid = LinuxSeccompArgBuilder::default()
+		.index(0usize)
+		.value(seccomp::OWN_PID)
+		.op(LinuxSeccompOperator::ScmpCmpEq)
+		.build()
+		.unwrap();
+	let signals = LinuxSyscallBuilder::default()
+		.names(to_names(QEMU_SIGNAL_SYSCALLS))
+		.action(LinuxSeccompAction::ScmpActAllow)
+		.args(vec![own_pid])
+		.build()
+		.unwrap();
+	// QEMU only creates threads, but no processes
+	let clone_thread = LinuxSeccompArgBuilder::default()
+		.index(0usize)
+		.value(libc::CLONE_THREAD as u64)
+		.value_two(libc::CLONE_THREAD as u64)
+		.op(LinuxSeccompOperator::ScmpCmpMaskedEq)
+		.build()
+		.unwrap();
+	let threads = LinuxSyscallBuilder::default()
+		.names(vec!["clone".to_string()])
+		.action(LinuxSeccompAction::ScmpActAllow)
+		.args(vec![clone_thread])
+		.build()
+		.unwrap();
+	// The flags of clone3 are passed in memory, which seccomp cannot inspect. With ENOSYS, the
+	// C library falls back to clone.
+	let clone3 = LinuxSyscallBuilder::default()
+		.names(vec!["clone3".to_string()])
+		.action(LinuxSeccompAction::ScmpActErrno)
+		.errno_ret(libc::ENOSYS as u32)
+		.build()
+		.unwrap();
+	LinuxSeccompBuilder::default()
+		.default_action(LinuxSeccompAction::ScmpActErrno)
+		.default_errno_ret(libc::EPERM as u32)
+		.architectures(vec![Arch::ScmpArchNative])
+		.syscalls(vec![allowed, signals, threads, clone3])
+		.build()
+		.unwrap()
+}
+
+/// Name of the QEMU binary, which emulates machines of the given architecture
+fn get_binary(architecture: Architecture) -> String {
+	format!("qemu-system-{architecture}")
+}
+
+fn get_qemu_args(
+	kernel: &str,
+	app: &str,
+	netconf: &NetworkConfig,
+	app_args: &[String],
+	vm_config: &VmConfig,
+	kvm_support: bool,
+	tap_fd: Option<RawFd>,
+) -> Vec<String> {
+	let smp = vm_config.smp();
+	let memory_size = format!("{}M", vm_config.memory_size());
+	debug!("Size of the VM: {smp} vCPUs, {memory_size} memory");
+
+	let architecture = vm_config.architecture();
+	// KVM is only able to run VMs of the host architecture, everything else is emulated
+	let kvm = vm_config.kvm() && kvm_support && architecture == Architecture::host();
+
+	// The VM is started by the supervisor through QMP, once QEMU has created it
+	let mut exec_args: Vec<String> = vec![
+		get_binary(architecture),
+		"-S".to_string(),
+		"-display".to_string(),
+		"none".to_string(),
+		"-smp".to_string(),
+		smp.to_string(),
+		"-m".to_string(),
+		memory_size.clone(),
+		"-serial".to_string(),
+		"stdio".to_string(),
+		"-qmp".to_string(),
+		format!("unix:{},server=on,wait=off", crate::consts::QMP_SOCKET),
+		"-kernel".to_string(),
+		kernel.to_string(),
+	];
+
+	// Deny QEMU to spawn processes and to gain privileges in addition to the seccomp profile
+	if crate::CONFIG.qemu_sandbox.unwrap_or(false) {
+		exec_args.push("-sandbox".to_string());
+		exec_args.push(
+			"on,obsolete=deny,elevateprivileges=deny,spawn=deny,resourcecontrol=deny".to_string(),
+		);
+	}
+
+	// Each architecture uses a different device, through which Hermit reports its exit code
+	let default_cpu_model = match architecture {
+		Architecture::X86_64 => {
+			exec_args.extend([
+				"-device".to_string(),
+				"isa-debug-exit,iobase=0xf4,iosize=0x04".to_string(),
+				"-initrd".to_string(),
+				app.to_string(),
+			]);
+			"qemu64,apic,fsgsbase,rdtscp,xsave,xsaveopt,fxsr,rdrand"
+		}
+		Architecture::Aarch64 => {
+			exec_args.extend([
+				"-machine".to_string(),
+				"virt,gic-version=3".to_string(),
+				"-semihosting".to_string(),
+				"-device".to_string(),
+				format!("guest-loader,addr=0x48000000,initrd={app}"),
+			]);
+			"cortex-a72"
+		}
+		Architecture::Riscv64 => {
+			// The exit device (sifive_test) is part of the virt machine
+			exec_args.extend([
+				"-machine".to_string(),
+				"virt".to_string(),
+				"-initrd".to_string(),
+				app.to_string(),
+			]);
+			"rv64"
+		}
+	};
+
+	if kvm {
+		exec_args.push("--enable-kvm".to_string());
+	}
+	exec_args.push("-cpu".to_string());
+	exec_args.push(match vm_config.cpu_model() {
+		Some(cpu_model) => cpu_model.clone(),
+		None if kvm => "host".to_string(),
+		None => default_cpu_model.to_string(),
+	});
+
+	if vm_config.micro_vm() {
+		exec_args.extend(
+			[
+				"-M",
+				"microvm,x-option-roms=off,pit=off,pic=off,rtc=on,auto-kernel-cmdline=off,acpi=off",
+				"-global",
+				"virtio-mmio.force-legacy=off",
+				"-nodefaults",
+				"-no-user-config",
+			]
+			.iter()
+			.map(|s| s.to_string()),
+		);
+	} else {
+		exec_args.extend([
+			"-chardev".to_string(),
+			"socket,id=char0,path=/run/vhostqemu".to_string(),
+			"-device".to_string(),
+			"vhost-user-fs-pci,queue-size=1024,chardev=char0,tag=root".to_string(),
+			"-object".to_string(),
+			// The shared memory backend has to match the memory size of the VM
+			format!("memory-backend-file,id=mem,size={memory_size},mem-path=/dev/shm,share=on"),
+			"-numa".to_string(),
+			"node,memdev=mem".to_string(),
+		]);
+	}
+
+	let mut args_string = match netconf {
+		NetworkConfig::TapNetwork(network_config) => {
+			exec_args.push("-netdev".to_string());
+			exec_args.push(format!("tap,id=net0,fd={}", tap_fd.unwrap()));
+			exec_args.push("-device".to_string());
+			exec_args.push(if vm_config.micro_vm() {
+				format!("virtio-net-device,netdev=net0,mac={}", network_config.mac)
+			} else {
+				format!(
+					"virtio-net-pci,netdev=net0,disable-legacy=on,mac={}",
+					network_config.mac
+				)
+			});
+			exec_args.push("-append".to_string());
+
+			let args_string = format!(
+				"-ip {} -gateway {} -mask {}",
+				network_config.ip, network_config.gateway, network_config.mask
+			);
+
+			args_string
+		}
+		NetworkConfig::TapDevice(tap_config) => {
+			exec_args.push("-netdev".to_string());
+			exec_args.push(format!(
+				"tap,id=net0,ifname={},script=no,downscript=no",
+				tap_config.name
+			));
+			exec_args.push("-device".to_string());
+			exec_args.push(if vm_config.micro_vm() {
+				format!("virtio-net-device,netdev=net0,mac={}", tap_config.mac)
+			} else {
+				format!(
+					"virtio-net-pci,netdev=net0,disable-legacy=on,mac={}",
+					tap_config.mac
+				)
+			});
+			exec_args.push("-append".to_string());
+
+			format!(
+				"-ip {} -gateway {} -mask {}",
+				tap_config.ip, tap_config.gateway, tap_config.mask
+			)
+		}
+		NetworkConfig::UserNetwork(user_port) => {
+			exec_args.extend([
+				"-netdev".to_string(),
+				format!(
+					"user,id=u1,hostfwd=tcp::{user_port}-:{user_port},net=192.168.76.0/24,dhcpstart=192.168.76.9"
+				),
+				"-device".to_string(),
+				"virtio-net-pci,netdev=u1,disable-legacy=on".to_string(),
+				"-append".to_string(),
+			]);
+
+			"".to_string()
+		}
+		NetworkConfig::None => {
+			exec_args.push("-append".to_string());
+			"".to_string()
+		}
+	};
+
+	if let Some(extra_kernel_args) = vm_config.extra_kernel_args() {
+		args_string = format!("{args_string} {extra_kernel_args}");
+	}
+	if let Some(application_args) = app_args.get(1..) {
+		args_string = format!("{} -- {}", args_string, application_args.join(" "));
+	}
+	exec_args.push(args_string);
+
+	exec_args
+}
+
+impl Hypervisor for Qemu {
+	fn memory_overhead(&self) -> u64 {
+		crate::CONFIG
+			.qemu_memory_overhead
+			.unwrap_or(DEFAULT_MEMORY_OVERHEAD)
+	}
+
+	fn supports_architecture(&self, _architecture: Architecture) -> bool {
+		// Foreign architectures are emulated by QEMU
+		true
+	}
+
+	fn check_installation(&self, vm_config: &VmConfig, rootfs: &Path) {
+		let binary = get_binary(vm_config.architecture());
+		if paths::find_in_path(Path::new(&binary), Some(rootfs)).is_none() {
+			panic!(
+				"Could not find the emulator {} in the container, which is required to run {} applications!",
+				binary,
+				vm_config.architecture()
+			);
+		}
+	}
+
+	fn mount_devices(&self, rootfs: &Path) {
+		devices::mount_kvm(rootfs, false);
+		devices::mount_vhost_net(rootfs);
+	}
+
+	fn decode_exit_code(&self, vm_config: &VmConfig, exit_code: i32) -> i32 {
+		// isa-debug-exit turns the exit code n of the application into (n << 1) | 1, whereas a
+		// regular shutdown of the VM leads to 0. Failures of QEMU before the VM was created are
+		// detected by the supervisor instead.
+		if vm_config.architecture() == Architecture::X86_64 && exit_code & 1 == 1 {
+			exit_code >> 1
+		} else {
+			exit_code
+		}
+	}
+
+	fn start_vm(&self, _vm_config: &VmConfig, created: &dyn Fn()) -> bool {
+		// QEMU starts with a stopped VM and only answers on QMP, once the machine was created
+		let start = Instant::now();
+		let mut connection = loop {
+			match qmp::QmpConnection::connect(Path::new(crate::consts::QMP_SOCKET)) {
+				Ok(connection) => break connection,
+				Err(err) if start.elapsed() > START_TIMEOUT => {
+					warn!("Could not connect to QEMU: {err}");
+					return false;
+				}
+				Err(_) => std::thread::sleep(Duration::from_millis(10)),
+			}
+		};
+		created();
+		if let Err(err) = connection.execute("cont", None) {
+			warn!("Could not start the VM: {err}");
+			return false;
+		}
+		true
+	}
+
+	fn request_shutdown(&self, vm_config: &VmConfig) -> bool {
+		// The microvm machine does not provide ACPI and therefore no power button
+		if vm_config.micro_vm() {
+			return false;
+		}
+		match qmp::QmpConnection::connect(Path::new(crate::consts::QMP_SOCKET))
+			.and_then(|mut connection| connection.execute("system_powerdown", None))
+		{
+			Ok(_) => true,
+			Err(err) => {
+				warn!("Could not request shutdown of the VM: {err}");
+				false
+			}
+		}
+	}
+
+	fn seccomp_profile(&self, _vm_config: &VmConfig) -> Option<LinuxSeccomp> {
+		if !crate::CONFIG.qemu_seccomp.unwrap_or(true) {
+			return None;
+		}
+
+		Some(get_seccomp_profile())
+	}
+
+	fn spawn_helpers(&self, vm_config: &VmConfig) {
+		// Without a microvm, the root directory is shared with the VM through virtiofs
+		if vm_config.micro_vm() {
+			return;
+		}
+
+		// The sandbox of virtiofsd requires privileges, but its seccomp filter does not
+		let seccomp = if crate::CONFIG.qemu_seccomp.unwrap_or(true) {
+			"kill"
+		} else {
+			"none"
+		};
+		let virtiofsd_args: Vec<&str> = vec![
+			"virtiofsd",
+			"--socket-path=/run/vhostqemu",
+			"--shared-dir",
+			"/root",
+			"--sandbox",
+			"none",
+			"--seccomp",
+			seccomp,
+			"--inode-file-handles=never",
+		];
+
+		info!("Initialize virtiofsd: {}", virtiofsd_args.join(" "));
+
+		let virtiofsd_path_rel = Path::new(
+			virtiofsd_args
+				.first()
+				.expect("Container spec does not contain any args!"),
+		);
+		let virtiofsd_path_abs = paths::find_in_path(virtiofsd_path_rel, None)
+			.expect("Could not determine location of args-executable!");
+
+		let mut cmd = std::process::Command::new(virtiofsd_path_abs);
+		cmd.arg0(virtiofsd_args.first().unwrap());
+		if virtiofsd_args.len() > 1 {
+			cmd.args(virtiofsd_args.get(1..).unwrap());
+		}
+		cmd.envs(std::env::vars());
+
+		#[allow(clippy::zombie_processes)]
+		let _child = cmd.spawn().expect("Unable to virtiofsd");
+	}
+
+	fn get_args(
+		&self,
+		app: &str,
+		app_args: &[String],
+		netconf: &NetworkConfig,
+		vm_config: &VmConfig,
+		tap_fd: Option<RawFd>,
+	) -> Vec<String> {
+		// QEMU boots the application through the Hermit loader, which is located next to it
+		let kernel_path = Path::new(app)
+			.parent()
+			.expect("App path does not have a parent!")
+			.join("hermit-loader");
+
+		get_qemu_args(
+			kernel_path.to_str().unwrap(),
+			app,
+			netconf,
+			app_args,
+			vm_config,
+			std::fs::metadata("/dev/kvm").is_ok(),
+			tap_fd,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn compile_seccomp_profile() {
+		let profile = get_seccomp_profile();
+		let filter = seccomp::compile_filter(&profile);
+
+		let known = |names: &[&str]| {
+			names
+				.iter()
+				.filter(|name| seccomp::get_syscall_nr(name).is_some())
+				.count()
+		};
+		// Architecture dispatch (and the x32 check on x86_64), three instructions for each allowed
+		// system call, the comparison with the own PID for the signals, the masked comparison of
+		// the clone flags, clone3 and the default action
+		let header = if cfg!(target_arch = "x86_64") { 7 } else { 4 };
+		assert_eq!(
+			filter.len(),
+			header + 3 * known(QEMU_SYSCALLS) + 7 * known(QEMU_SIGNAL_SYSCALLS) + 9 + 3 + 1
+		);
+		assert!(!QEMU_SYSCALLS.contains(&"execve"));
+		assert_eq!(known(QEMU_SIGNAL_SYSCALLS), QEMU_SIGNAL_SYSCALLS.len());
+
+		let allow = LinuxSeccompAction::ScmpActAllow.as_u32(None);
+		let deny = LinuxSeccompAction::ScmpActErrno.as_u32(Some(libc::EPERM as u32));
+		let returns: Vec<u32> = filter
+			.iter()
+			.filter(|instruction| instruction.code == (libc::BPF_RET | libc::BPF_K) as u16)
+			.map(|instruction| instruction.k)
+			.collect();
+		let enosys = LinuxSeccompAction::ScmpActErrno.as_u32(Some(libc::ENOSYS as u32));
+		assert!(returns
+			.iter()
+			.all(|action| *action == allow || *action == deny || *action == enosys));
+		assert_eq!(
+			returns.iter().filter(|action| **action == enosys).count(),
+			1
+		);
+		assert_eq!(filter.last().unwrap().k, deny);
+
+		// Only the comparisons with the own PID are patched on installation
+		let placeholders = filter
+			.iter()
+			.filter(|instruction| instruction.k == seccomp::OWN_PID as u32)
+			.count();
+		assert_eq!(placeholders, QEMU_SIGNAL_SYSCALLS.len());
+	}
+}
@@ -0,0 +1,112 @@
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{error::Error, fmt};
+
+const QMP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+struct QmpError {
+	details: String,
+}
+
+impl From<String> for QmpError {
+	fn from(msg: String) -> Self {
+		QmpError { details: msg }
+	}
+}
+
+impl fmt::Display for QmpError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.details)
+	}
+}
+
+impl Error for QmpError {
+	fn description(&self) -> &str {
+		&self.details
+	}
+}
+
+/// Returns the path under which the QMP socket of the VM running as `pid` is reachable from the host.
+pub fn get_socket_path(pid: i32) -> PathBuf {
+	PathBuf::from(format!("/proc/{pid}/root")).join(
+		Path::new(crate::consts::QMP_SOCKET)
+			.strip_prefix("/")
+			.unwrap(),
+	)
+}
+
+/// A connection to the QEMU Machine Protocol (QMP) server of a Hermit VM
+pub struct QmpConnection {
+	reader: BufReader<UnixStream>,
+	writer: UnixStream,
+}
+
+impl QmpConnection {
+	/// Connects to the QMP socket at `path` and leaves the capabilities negotiation mode.
+	pub fn connect(path: &Path) -> Result<Self, Box<dyn Error>> {
+		let stream = UnixStream::connect(path)?;
+		stream.set_read_timeout(Some(QMP_TIMEOUT))?;
+		stream.set_write_timeout(Some(QMP_TIMEOUT))?;
+
+		let mut connection = QmpConnection {
+			reader: BufReader::new(stream.try_clone()?),
+			writer: stream,
+		};
+
+		let greeting = connection.read_message()?;
+		if greeting.get("QMP").is_none() {
+			return Err(Box::new(QmpError::from(format!(
+				"Received invalid QMP greeting {greeting}"
+			))));
+		}
+		connection.execute("qmp_capabilities", None)?;
+
+		Ok(connection)
+	}
+
+	fn read_message(&mut self) -> Result<Value, Box<dyn Error>> {
+		let mut line = String::new();
+		if self.reader.read_line(&mut line)? == 0 {
+			return Err(Box::new(QmpError::from(
+				"QMP connection was closed by QEMU".to_string(),
+			)));
+		}
+		Ok(serde_json::from_str(&line)?)
+	}
+
+	/// Executes the QMP command `command` and returns its result.
+	/// Asynchronous events, which are received in the meantime, are ignored.
+	pub fn execute(
+		&mut self,
+		command: &str,
+		arguments: Option<Value>,
+	) -> Result<Value, Box<dyn Error>> {
+		let request = match arguments {
+			Some(arguments) => json!({ "execute": command, "arguments": arguments }),
+			None => json!({ "execute": command }),
+		};
+		writeln!(self.writer, "{request}")?;
+
+		loop {
+			let mut message = self.read_message()?;
+			if let Some(result) = message.get_mut("return") {
+				return Ok(result.take());
+			} else if let Some(error) = message.get("error") {
+				return Err(Box::new(QmpError::from(format!(
+					"QMP command {} failed: {}",
+					command,
+					error
+						.get("desc")
+						.and_then(Value::as_str)
+						.unwrap_or("unknown error")
+				))));
+			} else if let Some(event) = message.get("event") {
+				debug!("Ignoring QMP event {event} while waiting for command result");
+			}
+		}
+	}
+}
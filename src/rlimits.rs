@@ -0,0 +1,61 @@
+use oci_spec::runtime::{PosixRlimit, PosixRlimitType};
+
+fn get_resource(typ: PosixRlimitType) -> libc::__rlimit_resource_t {
+	match typ {
+		PosixRlimitType::RlimitCpu => libc::RLIMIT_CPU,
+		PosixRlimitType::RlimitFsize => libc::RLIMIT_FSIZE,
+		PosixRlimitType::RlimitData => libc::RLIMIT_DATA,
+		PosixRlimitType::RlimitStack => libc::RLIMIT_STACK,
+		PosixRlimitType::RlimitCore => libc::RLIMIT_CORE,
+		PosixRlimitType::RlimitRss => libc::RLIMIT_RSS,
+		PosixRlimitType::RlimitNproc => libc::RLIMIT_NPROC,
+		PosixRlimitType::RlimitNofile => libc::RLIMIT_NOFILE,
+		PosixRlimitType::RlimitMemlock => libc::RLIMIT_MEMLOCK,
+		PosixRlimitType::RlimitAs => libc::RLIMIT_AS,
+		PosixRlimitType::RlimitLocks => libc::RLIMIT_LOCKS,
+		PosixRlimitType::RlimitSigpending => libc::RLIMIT_SIGPENDING,
+		PosixRlimitType::RlimitMsgqueue => libc::RLIMIT_MSGQUEUE,
+		PosixRlimitType::RlimitNice => libc::RLIMIT_NICE,
+		PosixRlimitType::RlimitRtprio => libc::RLIMIT_RTPRIO,
+		PosixRlimitType::RlimitRttime => libc::RLIMIT_RTTIME,
+	}
+}
+
+/// Checks the rlimits of the spec, so that invalid limits are already rejected by runh create.
+/// Unknown types are rejected while parsing the spec.
+pub fn validate_rlimits(rlimits: &[PosixRlimit]) {
+	for (i, rlimit) in rlimits.iter().enumerate() {
+		if rlimits[..i].iter().any(|other| other.typ() == rlimit.typ()) {
+			panic!("Rlimit {:?} is specified more than once!", rlimit.typ());
+		}
+		if rlimit.soft() > rlimit.hard() {
+			panic!(
+				"Soft limit {} of rlimit {:?} exceeds the hard limit {}!",
+				rlimit.soft(),
+				rlimit.typ(),
+				rlimit.hard()
+			);
+		}
+	}
+}
+
+/// Sets the rlimits of the calling process. Raising hard limits requires CAP_SYS_RESOURCE,
+/// so this has to happen before the process switches to the requested user.
+pub fn set_rlimits(rlimits: &[PosixRlimit]) {
+	for rlimit in rlimits {
+		debug!(
+			"Set rlimit {:?} to {} (soft) / {} (hard)",
+			rlimit.typ(),
+			rlimit.soft(),
+			rlimit.hard()
+		);
+		let limit = libc::rlimit {
+			rlim_cur: rlimit.soft(),
+			rlim_max: rlimit.hard(),
+		};
+		let res =
+			unsafe { libc::prlimit(0, get_resource(rlimit.typ()), &limit, std::ptr::null_mut()) };
+		nix::errno::Errno::result(res)
+			.unwrap_or_else(|err| panic!("Could not set rlimit {:?}: {}", rlimit.typ(), err));
+	}
+}
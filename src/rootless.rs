@@ -0,0 +1,64 @@
+use nix::sched::CloneFlags;
+use nix::unistd::Uid;
+use oci_spec::runtime::Spec;
+use std::path::PathBuf;
+
+use crate::flags;
+use crate::hypervisor::HypervisorKind;
+use crate::vmconfig::{VmConfig, VmNetwork, ANNOTATION_PREFIX};
+
+/// runh runs rootless, if it is started by an unprivileged user. This includes root in a user
+/// namespace, e.g. inside of a rootless Podman, which lacks the privileges on the host.
+pub fn is_rootless() -> bool {
+	!Uid::effective().is_root()
+		|| std::env::var_os("_CONTAINERS_USERNS_CONFIGURED").is_some()
+		|| is_in_user_namespace()
+}
+
+/// Checks whether runh runs in a user namespace. Like runc, the initial user namespace is
+/// detected by its uid mapping, which covers all users of the host.
+fn is_in_user_namespace() -> bool {
+	std::fs::read_to_string("/proc/self/uid_map").is_ok_and(|uid_map| {
+		uid_map.split_whitespace().collect::<Vec<_>>() != ["0", "0", "4294967295"]
+	})
+}
+
+/// Default root directory for the container state. Rootless instances keep their state in the
+/// runtime directory of the user.
+pub fn get_default_root() -> PathBuf {
+	if is_rootless() {
+		if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+			return PathBuf::from(runtime_dir).join("runh");
+		}
+		return PathBuf::from(format!("/run/user/{}/runh", Uid::effective()));
+	}
+	PathBuf::from("/run/user/1000/runh")
+}
+
+/// Checks that the container is able to run without privileges on the host.
+pub fn validate_spec(spec: &Spec) {
+	let new_user_namespace = spec
+		.linux()
+		.as_ref()
+		.and_then(|linux| linux.namespaces().as_ref())
+		.is_some_and(|namespaces| {
+			flags::generate_cloneflags(namespaces).contains(CloneFlags::CLONE_NEWUSER)
+		});
+	// Root in a user namespace already has all capabilities within it
+	if !new_user_namespace && !Uid::effective().is_root() {
+		panic!("Rootless containers require a new user namespace with uid and gid mappings!");
+	}
+}
+
+/// Checks that the VM of a Hermit container is able to run without privileges on the host.
+pub fn validate_vm_config(vm_config: &VmConfig) {
+	if vm_config.hypervisor() == HypervisorKind::Firecracker {
+		panic!("Firecracker is not supported for rootless containers!");
+	}
+	if vm_config.network() == VmNetwork::Tap {
+		panic!(
+			"Rootless containers are not able to create tap devices! Use the annotation {}network to select another network.",
+			ANNOTATION_PREFIX
+		);
+	}
+}
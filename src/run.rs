@@ -0,0 +1,187 @@
+use crate::create::create_container;
+use crate::delete::delete_container;
+use crate::logging::LogLevel;
+use crate::start::start_container;
+use crate::state;
+use crate::{console, signals};
+use nix::sys::signal::SigmaskHow;
+use nix::sys::socket::{ControlMessageOwned, MsgFlags};
+use nix::sys::termios;
+use nix::sys::wait::WaitStatus;
+use nix::unistd::Pid;
+use oci_spec::runtime::Spec;
+use std::fs::File;
+use std::io::IoSliceMut;
+use std::os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::thread::JoinHandle;
+
+/// Console socket, on which runh receives the pty master in attached mode. The socket file is
+/// removed, when the listener is dropped, even if creating the container fails.
+struct ConsoleListener {
+	path: PathBuf,
+	listener: UnixListener,
+}
+
+impl Drop for ConsoleListener {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_file(&self.path);
+	}
+}
+
+fn receive_pty_master(listener: &UnixListener) -> OwnedFd {
+	let (stream, _) = listener
+		.accept()
+		.expect("Could not accept connection on console socket!");
+
+	let mut buffer = [0u8; 4096];
+	let mut iov = [IoSliceMut::new(&mut buffer)];
+	let mut cmsg_buffer = nix::cmsg_space!([RawFd; 1]);
+	let message = nix::sys::socket::recvmsg::<()>(
+		stream.as_raw_fd(),
+		&mut iov,
+		Some(&mut cmsg_buffer),
+		MsgFlags::empty(),
+	)
+	.expect("Could not receive message from console socket!");
+
+	for cmsg in message
+		.cmsgs()
+		.expect("Could not parse console socket message!")
+	{
+		if let ControlMessageOwned::ScmRights(fds) = cmsg {
+			if let Some(fd) = fds.first() {
+				return unsafe { OwnedFd::from_raw_fd(*fd) };
+			}
+		}
+	}
+	panic!("Console socket message did not contain the pty master!");
+}
+
+fn attach_pty(master: OwnedFd) -> JoinHandle<()> {
+	let mut master_in = File::from(master);
+	let mut master_out = master_in
+		.try_clone()
+		.expect("Could not duplicate pty master!");
+
+	std::thread::spawn(move || {
+		let _ = std::io::copy(&mut std::io::stdin(), &mut master_in);
+	});
+
+	// Reading from the master fails with EIO as soon as the container closed the pty slave
+	std::thread::spawn(move || {
+		let _ = std::io::copy(&mut master_out, &mut std::io::stdout());
+	})
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run_container(
+	project_dir: PathBuf,
+	id: &str,
+	bundle: PathBuf,
+	pidfile: Option<PathBuf>,
+	console_socket: Option<PathBuf>,
+	detach: bool,
+	debug_config: bool,
+	child_log_level: LogLevel,
+	systemd_cgroup: bool,
+) {
+	if detach {
+		create_container(
+			project_dir.clone(),
+			id,
+			bundle,
+			pidfile,
+			console_socket,
+			debug_config,
+			child_log_level,
+			false,
+			systemd_cgroup,
+		);
+		start_container(project_dir, id);
+		return;
+	}
+
+	let spec = Spec::load(bundle.join("config.json")).expect("Unable to load config file");
+	let terminal = spec
+		.process()
+		.as_ref()
+		.and_then(|process| process.terminal())
+		.unwrap_or(false);
+
+	// In attached mode, we receive the pty master on our own console socket
+	let console_listener = if terminal {
+		let path = project_dir.join(format!("{id}-console.sock"));
+		let _ = std::fs::remove_file(&path);
+		let listener = UnixListener::bind(&path).expect("Could not create console socket!");
+		Some(ConsoleListener { path, listener })
+	} else {
+		None
+	};
+
+	create_container(
+		project_dir.clone(),
+		id,
+		bundle,
+		pidfile,
+		console_listener
+			.as_ref()
+			.map(|console| console.path.clone()),
+		debug_config,
+		child_log_level,
+		!terminal,
+		systemd_cgroup,
+	);
+
+	let pid = state::get_container_state(project_dir.clone(), id)
+		.and_then(|state| state.pid)
+		.expect("Could not determine PID of the created container!");
+
+	let pty_master = console_listener.map(|console| receive_pty_master(&console.listener));
+	let master_fd = pty_master.as_ref().map(|master| master.as_raw_fd());
+
+	let signals = signals::forward_signals(Pid::from_raw(pid), master_fd);
+
+	let stdin = std::io::stdin();
+	let saved_termios = if pty_master.is_some() {
+		termios::tcgetattr(stdin.as_fd()).ok().inspect(|attrs| {
+			let mut raw_attrs = attrs.clone();
+			termios::cfmakeraw(&mut raw_attrs);
+			termios::tcsetattr(stdin.as_fd(), termios::SetArg::TCSANOW, &raw_attrs)
+				.expect("Could not set terminal to raw mode!");
+		})
+	} else {
+		None
+	};
+	if let Some(master_fd) = master_fd {
+		console::copy_winsize(master_fd);
+	}
+	let output_forwarder = pty_master.map(attach_pty);
+
+	start_container(project_dir.clone(), id);
+
+	// The container process was cloned with CLONE_PARENT and is therefore our child
+	let exit_code = match nix::sys::wait::waitpid(Pid::from_raw(pid), None) {
+		Ok(WaitStatus::Exited(_, code)) => code,
+		Ok(WaitStatus::Signaled(_, signal, _)) => 128 + signal as i32,
+		Ok(status) => panic!("Unexpected wait status {:?} for container process!", status),
+		Err(err) => panic!("Could not wait for container process: {}", err),
+	};
+
+	if let Some(output_forwarder) = output_forwarder {
+		output_forwarder
+			.join()
+			.expect("Terminal forwarder did panic!");
+	}
+	if let Some(attrs) = saved_termios {
+		let _ = termios::tcsetattr(stdin.as_fd(), termios::SetArg::TCSANOW, &attrs);
+	}
+
+	info!("Container exited with exit code {exit_code}");
+	nix::sys::signal::pthread_sigmask(SigmaskHow::SIG_UNBLOCK, Some(&signals), None)
+		.expect("Could not unblock forwarded signals!");
+	delete_container(project_dir, id, false);
+
+	std::process::exit(exit_code);
+}
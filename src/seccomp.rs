@@ -0,0 +1,723 @@
+#[cfg(target_arch = "x86_64")]
+use crate::syscalls::X32_SYSCALL_BIT;
+use crate::syscalls::{COMPAT_SYSCALLS, SYSCALLS};
+use nix::sys::socket::{self, ControlMessage, MsgFlags};
+use oci_spec::runtime::{
+	Arch, LinuxSeccomp, LinuxSeccompAction, LinuxSeccompArg, LinuxSeccompFilterFlag,
+	LinuxSeccompOperator,
+};
+use serde_json::json;
+use std::convert::TryInto;
+use std::io::IoSlice;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::net::UnixStream;
+
+#[cfg(target_arch = "x86_64")]
+const NATIVE_ARCH: Arch = Arch::ScmpArchX86_64;
+#[cfg(target_arch = "aarch64")]
+const NATIVE_ARCH: Arch = Arch::ScmpArchAarch64;
+#[cfg(target_arch = "riscv64")]
+const NATIVE_ARCH: Arch = Arch::ScmpArchRiscv64;
+
+// Offsets within struct seccomp_data
+const OFFSET_NR: u32 = 0;
+const OFFSET_ARCH: u32 = 4;
+const OFFSET_ARGS: u32 = 16;
+
+/// Target of a conditional jump within the instructions of a single rule
+#[derive(Clone, Copy)]
+enum Target {
+	/// Continue with the next instruction
+	Next,
+	/// The current argument condition is fulfilled, continue with the next one
+	Pass,
+	/// The condition is not fulfilled, continue with the next rule
+	Fail,
+}
+
+enum Instruction {
+	Load(u32),
+	And(u32),
+	Jump(u32, u32, Target, Target),
+}
+
+fn stmt(code: u32, k: u32) -> libc::sock_filter {
+	libc::sock_filter {
+		code: code as u16,
+		jt: 0,
+		jf: 0,
+		k,
+	}
+}
+
+fn jump(op: u32, k: u32, jt: usize, jf: usize) -> libc::sock_filter {
+	libc::sock_filter {
+		code: (libc::BPF_JMP | op | libc::BPF_K) as u16,
+		jt: jt.try_into().expect("Seccomp rule is too large!"),
+		jf: jf.try_into().expect("Seccomp rule is too large!"),
+		k,
+	}
+}
+
+fn load(offset: u32) -> libc::sock_filter {
+	stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, offset)
+}
+
+fn ret(action: u32) -> libc::sock_filter {
+	stmt(libc::BPF_RET | libc::BPF_K, action)
+}
+
+/// Compiles a comparison of an argument. As classic BPF only operates on 32-bit words, the upper
+/// half of 64-bit (`wide`) arguments is compared first and the lower half only, if the upper
+/// halves are equal. Like libseccomp, only the lower half is compared on 32-bit architectures.
+fn compile_condition(arg: &LinuxSeccompArg, wide: bool) -> Vec<Instruction> {
+	use Instruction::*;
+	use Target::*;
+
+	if arg.index() >= 6 {
+		panic!(
+			"System calls do not have an argument with index {}!",
+			arg.index()
+		);
+	}
+	// All supported architectures are little endian
+	let offset_low = OFFSET_ARGS + 8 * arg.index() as u32;
+	let offset_high = offset_low + 4;
+	let high = (arg.value() >> 32) as u32;
+	let low = arg.value() as u32;
+
+	let (upper, lower) = match arg.op() {
+		LinuxSeccompOperator::ScmpCmpEq => (
+			vec![Jump(libc::BPF_JEQ, high, Next, Fail)],
+			vec![Jump(libc::BPF_JEQ, low, Pass, Fail)],
+		),
+		LinuxSeccompOperator::ScmpCmpNe => (
+			vec![Jump(libc::BPF_JEQ, high, Next, Pass)],
+			vec![Jump(libc::BPF_JEQ, low, Fail, Pass)],
+		),
+		LinuxSeccompOperator::ScmpCmpGt => (
+			vec![
+				Jump(libc::BPF_JGT, high, Pass, Next),
+				Jump(libc::BPF_JEQ, high, Next, Fail),
+			],
+			vec![Jump(libc::BPF_JGT, low, Pass, Fail)],
+		),
+		LinuxSeccompOperator::ScmpCmpGe => (
+			vec![
+				Jump(libc::BPF_JGT, high, Pass, Next),
+				Jump(libc::BPF_JEQ, high, Next, Fail),
+			],
+			vec![Jump(libc::BPF_JGE, low, Pass, Fail)],
+		),
+		LinuxSeccompOperator::ScmpCmpLt => (
+			vec![
+				Jump(libc::BPF_JGT, high, Fail, Next),
+				Jump(libc::BPF_JEQ, high, Next, Pass),
+			],
+			vec![Jump(libc::BPF_JGE, low, Fail, Pass)],
+		),
+		LinuxSeccompOperator::ScmpCmpLe => (
+			vec![
+				Jump(libc::BPF_JGT, high, Fail, Next),
+				Jump(libc::BPF_JEQ, high, Next, Pass),
+			],
+			vec![Jump(libc::BPF_JGT, low, Fail, Pass)],
+		),
+		LinuxSeccompOperator::ScmpCmpMaskedEq => {
+			// The argument is masked with value and compared with valueTwo
+			let expected = arg.value_two().unwrap_or(0);
+			(
+				vec![
+					And(high),
+					Jump(libc::BPF_JEQ, (expected >> 32) as u32, Next, Fail),
+				],
+				vec![And(low), Jump(libc::BPF_JEQ, expected as u32, Pass, Fail)],
+			)
+		}
+	};
+
+	let mut condition = Vec::new();
+	if wide {
+		condition.push(Load(offset_high));
+		condition.extend(upper);
+	}
+	condition.push(Load(offset_low));
+	condition.extend(lower);
+	condition
+}
+
+/// Compiles a rule for the system call `nr`. If the number does not match or one of the argument
+/// conditions fails, the rule falls through to the next one.
+fn compile_rule(
+	nr: u32,
+	args: &[LinuxSeccompArg],
+	action: u32,
+	wide: bool,
+) -> Vec<libc::sock_filter> {
+	let conditions: Vec<Vec<Instruction>> = args
+		.iter()
+		.map(|arg| compile_condition(arg, wide))
+		.collect();
+	let conditions_len: usize = conditions.iter().map(Vec::len).sum();
+
+	let mut filter = vec![load(OFFSET_NR)];
+	// The rule ends with the return of the action
+	filter.push(jump(libc::BPF_JEQ, nr, 0, conditions_len + 1));
+
+	let mut remaining = conditions_len + 1;
+	for condition in conditions {
+		let len = condition.len();
+		for (i, instruction) in condition.into_iter().enumerate() {
+			remaining -= 1;
+			let resolve = |target| match target {
+				Target::Next => 0,
+				Target::Pass => len - i - 1,
+				Target::Fail => remaining,
+			};
+			filter.push(match instruction {
+				Instruction::Load(offset) => load(offset),
+				Instruction::And(mask) => stmt(libc::BPF_ALU | libc::BPF_AND | libc::BPF_K, mask),
+				Instruction::Jump(op, k, jt, jf) => jump(op, k, resolve(jt), resolve(jf)),
+			});
+		}
+	}
+	filter.push(ret(action));
+	filter
+}
+
+#[cfg(test)]
+pub fn get_syscall_nr(name: &str) -> Option<u32> {
+	find_syscall_nr(SYSCALLS, name)
+}
+
+fn find_syscall_nr(table: &[(&str, u32)], name: &str) -> Option<u32> {
+	table
+		.iter()
+		.find(|(syscall, _)| *syscall == name)
+		.map(|(_, nr)| *nr)
+}
+
+fn jump_always(offset: usize) -> libc::sock_filter {
+	stmt(
+		libc::BPF_JMP | libc::BPF_JA,
+		offset.try_into().expect("Seccomp filter is too large!"),
+	)
+}
+
+/// Compiles the rules of the profile for an architecture with the system calls `table`. The
+/// section ends with the default action.
+fn compile_section(
+	seccomp: &LinuxSeccomp,
+	table: &[(&str, u32)],
+	wide: bool,
+	default_action: u32,
+) -> Vec<libc::sock_filter> {
+	let mut filter = Vec::new();
+	for syscall in seccomp.syscalls().iter().flatten() {
+		let action = syscall.action().as_u32(syscall.errno_ret());
+		if action == default_action {
+			continue;
+		}
+		for name in syscall.names() {
+			// Profiles usually contain system calls of newer kernels or other architectures
+			let Some(nr) = find_syscall_nr(table, name) else {
+				debug!("Ignore unknown system call {name} in seccomp profile");
+				continue;
+			};
+			filter.extend(compile_rule(
+				nr,
+				syscall.args().as_deref().unwrap_or_default(),
+				action,
+				wide,
+			));
+		}
+	}
+	filter.push(ret(default_action));
+	filter
+}
+
+/// Compiles the seccomp profile into a classic BPF program. Rules are checked in the order of
+/// the profile and system calls without a matching rule lead to the default action. Besides the
+/// native architecture, the rules are compiled for the listed architectures, whose applications
+/// are able to run on the host. Other architectures cannot occur and are ignored.
+pub fn compile_filter(seccomp: &LinuxSeccomp) -> Vec<libc::sock_filter> {
+	let default_action = seccomp.default_action().as_u32(seccomp.default_errno_ret());
+
+	let architectures = seccomp.architectures().clone().unwrap_or_default();
+	if !architectures.is_empty()
+		&& !architectures
+			.iter()
+			.any(|arch| *arch == NATIVE_ARCH || *arch == Arch::ScmpArchNative)
+	{
+		warn!("The seccomp profile does not contain the native architecture {NATIVE_ARCH}!");
+	}
+
+	// Sections of the architectures, which are selected by the architecture of the system call
+	let mut sections = vec![(
+		NATIVE_ARCH,
+		compile_section(seccomp, SYSCALLS, true, default_action),
+	)];
+	#[cfg(target_arch = "x86_64")]
+	let mut x32_section = None;
+	for (arch, table) in COMPAT_SYSCALLS {
+		if !architectures.contains(arch) {
+			continue;
+		}
+		let Some(table) = table else {
+			panic!(
+				"The seccomp profile contains the architecture {}, which runh is not able to filter!",
+				arch
+			);
+		};
+		let section = compile_section(seccomp, table, false, default_action);
+		// System calls of the x32 ABI are distinguished by their number instead
+		#[cfg(target_arch = "x86_64")]
+		if *arch == Arch::ScmpArchX32 {
+			x32_section = Some(section);
+			continue;
+		}
+		sections.push((*arch, section));
+	}
+
+	let mut filter = vec![load(OFFSET_ARCH)];
+	let mut section_jumps = Vec::new();
+	for (arch, _) in &sections {
+		filter.push(jump(libc::BPF_JEQ, *arch as u32, 0, 1));
+		section_jumps.push(filter.len());
+		filter.push(jump_always(0));
+	}
+	// System calls of other architectures are not covered by the rules
+	filter.push(ret(default_action));
+
+	#[cfg(target_arch = "x86_64")]
+	let mut x32_jump = None;
+	for (section_jump, (arch, section)) in section_jumps.into_iter().zip(sections) {
+		filter[section_jump] = jump_always(filter.len() - section_jump - 1);
+		#[cfg(target_arch = "x86_64")]
+		if arch == NATIVE_ARCH {
+			filter.extend([load(OFFSET_NR), jump(libc::BPF_JGE, X32_SYSCALL_BIT, 0, 1)]);
+			if x32_section.is_some() {
+				x32_jump = Some(filter.len());
+				filter.push(jump_always(0));
+			} else {
+				filter.push(ret(default_action));
+			}
+		}
+		#[cfg(not(target_arch = "x86_64"))]
+		let _ = arch;
+		filter.extend(section);
+	}
+	#[cfg(target_arch = "x86_64")]
+	if let (Some(x32_jump), Some(x32_section)) = (x32_jump, x32_section) {
+		filter[x32_jump] = jump_always(filter.len() - x32_jump - 1);
+		filter.extend(x32_section);
+	}
+	filter
+}
+
+/// Placeholder for the PID of the process, which installs a precompiled filter. As the PID of a
+/// child is not known before it is forked, comparisons with the placeholder are patched by
+/// `install_filter`. The value is larger than any PID.
+pub const OWN_PID: u64 = 0xfffffffe;
+
+/// Installs a precompiled filter. As it neither allocates nor logs, it is safe to call between
+/// fork and exec.
+pub fn install_filter(filter: &mut [libc::sock_filter]) -> std::io::Result<()> {
+	let pid = unsafe { libc::getpid() } as u32;
+	for instruction in filter.iter_mut() {
+		if instruction.code == (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16
+			&& instruction.k == OWN_PID as u32
+		{
+			instruction.k = pid;
+		}
+	}
+	let program = libc::sock_fprog {
+		len: filter.len() as libc::c_ushort,
+		filter: filter.as_ptr() as *mut libc::sock_filter,
+	};
+	// Unprivileged processes are only allowed to install filters without gaining privileges
+	if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+		return Err(std::io::Error::last_os_error());
+	}
+	let res = unsafe {
+		libc::syscall(
+			libc::SYS_seccomp,
+			libc::SECCOMP_SET_MODE_FILTER,
+			0,
+			&program as *const libc::sock_fprog,
+		)
+	};
+	if res != 0 {
+		return Err(std::io::Error::last_os_error());
+	}
+	Ok(())
+}
+
+fn uses_notify(seccomp: &LinuxSeccomp) -> bool {
+	seccomp.default_action() == LinuxSeccompAction::ScmpActNotify
+		|| seccomp
+			.syscalls()
+			.iter()
+			.flatten()
+			.any(|syscall| syscall.action() == LinuxSeccompAction::ScmpActNotify)
+}
+
+/// Connects to the seccomp agent, if the profile forwards system calls to it. The listener path
+/// is only reachable, before the container switches into its rootfs.
+pub fn connect_listener(seccomp: &LinuxSeccomp) -> Option<UnixStream> {
+	if !uses_notify(seccomp) {
+		return None;
+	}
+	let listener_path = seccomp
+		.listener_path()
+		.as_ref()
+		.expect("The seccomp profile uses SCMP_ACT_NOTIFY, but does not define a listenerPath!");
+	Some(UnixStream::connect(listener_path).unwrap_or_else(|err| {
+		panic!(
+			"Could not connect to seccomp agent at {:?}: {}",
+			listener_path, err
+		)
+	}))
+}
+
+/// Loads the seccomp filter into the calling process. If a listener is given, the notification
+/// fd of the filter is passed to the seccomp agent together with the container state.
+pub fn load_filter(
+	seccomp: &LinuxSeccomp,
+	listener: Option<UnixStream>,
+	state: &serde_json::Value,
+) {
+	let mut filter = compile_filter(seccomp);
+	debug!("Load seccomp filter with {} instructions", filter.len());
+	let program = libc::sock_fprog {
+		len: filter
+			.len()
+			.try_into()
+			.expect("Seccomp filter is too large!"),
+		filter: filter.as_mut_ptr(),
+	};
+
+	let mut flags: libc::c_ulong = 0;
+	for flag in seccomp.flags().iter().flatten() {
+		flags |= match flag {
+			LinuxSeccompFilterFlag::SeccompFilterFlagLog => libc::SECCOMP_FILTER_FLAG_LOG,
+			LinuxSeccompFilterFlag::SeccompFilterFlagTsync => libc::SECCOMP_FILTER_FLAG_TSYNC,
+			LinuxSeccompFilterFlag::SeccompFilterFlagSpecAllow => {
+				libc::SECCOMP_FILTER_FLAG_SPEC_ALLOW
+			}
+			LinuxSeccompFilterFlag::SeccompFilterFlagWaitKillableRecv => {
+				libc::SECCOMP_FILTER_FLAG_WAIT_KILLABLE_RECV
+			}
+		};
+	}
+	if listener.is_some() {
+		flags |= libc::SECCOMP_FILTER_FLAG_NEW_LISTENER;
+	}
+
+	let res = unsafe {
+		libc::syscall(
+			libc::SYS_seccomp,
+			libc::SECCOMP_SET_MODE_FILTER,
+			flags,
+			&program as *const libc::sock_fprog,
+		)
+	};
+	let res = nix::errno::Errno::result(res)
+		.unwrap_or_else(|err| panic!("Could not load seccomp filter: {}", err));
+
+	if let Some(listener) = listener {
+		let notify_fd = unsafe { OwnedFd::from_raw_fd(res as i32) };
+		// The message follows the container process state used by runc and crun
+		let message = json!({
+			"ociVersion": crate::consts::OCI_STATE_VERSION,
+			"fds": ["seccompFd"],
+			"pid": state["pid"],
+			"metadata": seccomp.listener_metadata().clone().unwrap_or_default(),
+			"state": state,
+		})
+		.to_string();
+		let fds = [notify_fd.as_raw_fd()];
+		socket::sendmsg::<()>(
+			listener.as_raw_fd(),
+			&[IoSlice::new(message.as_bytes())],
+			&[ControlMessage::ScmRights(&fds)],
+			MsgFlags::empty(),
+			None,
+		)
+		.unwrap_or_else(|err| panic!("Could not send seccomp fd to seccomp agent: {}", err));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use oci_spec::runtime::{LinuxSeccompArgBuilder, LinuxSeccompBuilder, LinuxSyscallBuilder};
+
+	const ALLOW: u32 = 0x7fff0000;
+	const ERRNO: u32 = 0x00050001;
+
+	/// Interprets the classic BPF instructions, which are emitted by the compiler, for a system
+	/// call with the given architecture, number and arguments.
+	fn run_filter(filter: &[libc::sock_filter], arch: u32, nr: u32, args: [u64; 6]) -> u32 {
+		let mut data = Vec::new();
+		data.extend(nr.to_ne_bytes());
+		data.extend(arch.to_ne_bytes());
+		// Instruction pointer
+		data.extend(0u64.to_ne_bytes());
+		for arg in args {
+			data.extend(arg.to_ne_bytes());
+		}
+
+		let mut acc = 0u32;
+		let mut pc = 0;
+		loop {
+			let instruction = filter[pc];
+			let code = instruction.code as u32;
+			let k = instruction.k;
+			pc += 1;
+			if code == libc::BPF_LD | libc::BPF_W | libc::BPF_ABS {
+				let offset = k as usize;
+				acc = u32::from_ne_bytes(data[offset..offset + 4].try_into().unwrap());
+			} else if code == libc::BPF_ALU | libc::BPF_AND | libc::BPF_K {
+				acc &= k;
+			} else if code == libc::BPF_RET | libc::BPF_K {
+				return k;
+			} else if code == libc::BPF_JMP | libc::BPF_JA {
+				pc += k as usize;
+			} else if code == libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K {
+				pc += if acc == k {
+					instruction.jt
+				} else {
+					instruction.jf
+				} as usize;
+			} else if code == libc::BPF_JMP | libc::BPF_JGT | libc::BPF_K {
+				pc += if acc > k {
+					instruction.jt
+				} else {
+					instruction.jf
+				} as usize;
+			} else if code == libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K {
+				pc += if acc >= k {
+					instruction.jt
+				} else {
+					instruction.jf
+				} as usize;
+			} else {
+				panic!("Unexpected instruction {:#x}", code);
+			}
+		}
+	}
+
+	fn arg(index: usize, op: LinuxSeccompOperator, value: u64, value_two: u64) -> LinuxSeccompArg {
+		LinuxSeccompArgBuilder::default()
+			.index(index)
+			.op(op)
+			.value(value)
+			.value_two(value_two)
+			.build()
+			.unwrap()
+	}
+
+	#[test]
+	fn compile_conditions() {
+		use LinuxSeccompOperator::*;
+
+		// The values differ in the upper and in the lower half of the compared value
+		let value = 0x1_0000_0005;
+		let probes = [
+			0,
+			5,
+			0x1_0000_0004,
+			value,
+			0x1_0000_0006,
+			0x0_ffff_ffff,
+			0x2_0000_0000,
+			u64::MAX,
+		];
+		type Predicate = fn(u64) -> bool;
+		let operators: [(LinuxSeccompOperator, Predicate); 6] = [
+			(ScmpCmpEq, |arg| arg == 0x1_0000_0005),
+			(ScmpCmpNe, |arg| arg != 0x1_0000_0005),
+			(ScmpCmpGt, |arg| arg > 0x1_0000_0005),
+			(ScmpCmpGe, |arg| arg >= 0x1_0000_0005),
+			(ScmpCmpLt, |arg| arg < 0x1_0000_0005),
+			(ScmpCmpLe, |arg| arg <= 0x1_0000_0005),
+		];
+
+		for (op, expected) in operators {
+			// The rule falls through to the end of the program, if it does not match
+			let mut filter = compile_rule(42, &[arg(2, op, value, 0)], ERRNO, true);
+			filter.push(ret(ALLOW));
+			for probe in probes {
+				let result = run_filter(&filter, NATIVE_ARCH as u32, 42, [0, 0, probe, 0, 0, 0]);
+				assert_eq!(
+					result == ERRNO,
+					expected(probe),
+					"{:?} with argument {:#x}",
+					op,
+					probe
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn compile_masked_condition() {
+		let mask = 0xff00_0000_00f0;
+		let expected = 0x1200_0000_0030;
+		let mut filter = compile_rule(
+			42,
+			&[arg(
+				0,
+				LinuxSeccompOperator::ScmpCmpMaskedEq,
+				mask,
+				expected,
+			)],
+			ERRNO,
+			true,
+		);
+		filter.push(ret(ALLOW));
+		for probe in [
+			0,
+			expected,
+			expected | 0x0f,
+			expected | 0xff_0000_0000_0000,
+			expected ^ 0x10,
+			expected ^ 0x0100_0000_0000,
+		] {
+			let result = run_filter(&filter, NATIVE_ARCH as u32, 42, [probe, 0, 0, 0, 0, 0]);
+			assert_eq!(result == ERRNO, probe & mask == expected, "{:#x}", probe);
+		}
+	}
+
+	#[test]
+	fn compile_rule_with_multiple_conditions() {
+		let mut filter = compile_rule(
+			42,
+			&[
+				arg(0, LinuxSeccompOperator::ScmpCmpEq, 1, 0),
+				arg(1, LinuxSeccompOperator::ScmpCmpGt, 2, 0),
+			],
+			ERRNO,
+			true,
+		);
+		filter.push(ret(ALLOW));
+		let arch = NATIVE_ARCH as u32;
+		assert_eq!(run_filter(&filter, arch, 42, [1, 3, 0, 0, 0, 0]), ERRNO);
+		assert_eq!(run_filter(&filter, arch, 42, [1, 2, 0, 0, 0, 0]), ALLOW);
+		assert_eq!(run_filter(&filter, arch, 42, [0, 3, 0, 0, 0, 0]), ALLOW);
+		assert_eq!(run_filter(&filter, arch, 43, [1, 3, 0, 0, 0, 0]), ALLOW);
+	}
+
+	#[test]
+	fn compile_profile() {
+		let seccomp = LinuxSeccompBuilder::default()
+			.default_action(LinuxSeccompAction::ScmpActAllow)
+			.syscalls(vec![
+				LinuxSyscallBuilder::default()
+					.names(vec!["unknown_syscall".to_string(), "getpid".to_string()])
+					.action(LinuxSeccompAction::ScmpActErrno)
+					.build()
+					.unwrap(),
+				// Rules with the default action are skipped
+				LinuxSyscallBuilder::default()
+					.names(vec!["getppid".to_string()])
+					.action(LinuxSeccompAction::ScmpActAllow)
+					.build()
+					.unwrap(),
+			])
+			.build()
+			.unwrap();
+		let filter = compile_filter(&seccomp);
+		let arch = NATIVE_ARCH as u32;
+		let getpid = libc::SYS_getpid as u32;
+
+		assert_eq!(run_filter(&filter, arch, getpid, [0; 6]), ERRNO);
+		assert_eq!(
+			run_filter(&filter, arch, libc::SYS_getppid as u32, [0; 6]),
+			ALLOW
+		);
+		// Foreign architectures are not covered by the rules
+		assert_eq!(run_filter(&filter, arch + 1, getpid, [0; 6]), ALLOW);
+		#[cfg(target_arch = "x86_64")]
+		assert_eq!(
+			run_filter(&filter, arch, getpid | X32_SYSCALL_BIT, [0; 6]),
+			ALLOW
+		);
+	}
+
+	#[test]
+	#[cfg(target_arch = "x86_64")]
+	fn compile_compat_architectures() {
+		let rule = |name: &str, args| {
+			LinuxSyscallBuilder::default()
+				.names(vec![name.to_string()])
+				.action(LinuxSeccompAction::ScmpActErrno)
+				.args(args)
+				.build()
+				.unwrap()
+		};
+		let seccomp = LinuxSeccompBuilder::default()
+			.default_action(LinuxSeccompAction::ScmpActAllow)
+			.architectures(vec![
+				Arch::ScmpArchX86_64,
+				Arch::ScmpArchX86,
+				Arch::ScmpArchX32,
+				// Architectures, which cannot run on the host, are ignored
+				Arch::ScmpArchS390x,
+			])
+			.syscalls(vec![
+				rule("getpid", vec![]),
+				rule(
+					"close",
+					vec![arg(0, LinuxSeccompOperator::ScmpCmpEq, u64::MAX, 0)],
+				),
+			])
+			.build()
+			.unwrap();
+		let filter = compile_filter(&seccomp);
+		let x86_64 = Arch::ScmpArchX86_64 as u32;
+		let x86 = Arch::ScmpArchX86 as u32;
+
+		assert_eq!(run_filter(&filter, x86_64, 39, [0; 6]), ERRNO);
+		assert_eq!(run_filter(&filter, x86_64, 110, [0; 6]), ALLOW);
+		// getpid and getppid of i386
+		assert_eq!(run_filter(&filter, x86, 20, [0; 6]), ERRNO);
+		assert_eq!(run_filter(&filter, x86, 64, [0; 6]), ALLOW);
+		// getpid of x32
+		assert_eq!(
+			run_filter(&filter, x86_64, X32_SYSCALL_BIT | 39, [0; 6]),
+			ERRNO
+		);
+
+		// Only the lower half of arguments is compared on 32-bit architectures
+		assert_eq!(
+			run_filter(&filter, x86, 6, [0xffffffff, 0, 0, 0, 0, 0]),
+			ERRNO
+		);
+		assert_eq!(
+			run_filter(&filter, x86, 6, [0xfffffffe, 0, 0, 0, 0, 0]),
+			ALLOW
+		);
+		assert_eq!(
+			run_filter(&filter, x86_64, 3, [0xffffffff, 0, 0, 0, 0, 0]),
+			ALLOW
+		);
+		assert_eq!(
+			run_filter(&filter, x86_64, 3, [u64::MAX, 0, 0, 0, 0, 0]),
+			ERRNO
+		);
+	}
+
+	#[test]
+	fn syscall_numbers() {
+		assert_eq!(get_syscall_nr("read"), Some(libc::SYS_read as u32));
+		assert_eq!(get_syscall_nr("execve"), Some(libc::SYS_execve as u32));
+		assert_eq!(get_syscall_nr("unknown_syscall"), None);
+		// Names must not occur twice
+		let mut names: Vec<&str> = SYSCALLS.iter().map(|(name, _)| *name).collect();
+		names.sort_unstable();
+		names.dedup();
+		assert_eq!(names.len(), SYSCALLS.len());
+	}
+}
@@ -0,0 +1,45 @@
+use crate::console;
+use nix::sys::signal::{SigSet, SigmaskHow, Signal};
+use nix::unistd::Pid;
+use std::os::unix::io::RawFd;
+
+/// Signals, which are forwarded to the container process while runh is attached to it.
+/// The supervisor of Hermit VMs forwards the same signals to the hypervisor.
+pub const FORWARDED_SIGNALS: [Signal; 8] = [
+	Signal::SIGHUP,
+	Signal::SIGINT,
+	Signal::SIGQUIT,
+	Signal::SIGTERM,
+	Signal::SIGUSR1,
+	Signal::SIGUSR2,
+	Signal::SIGCONT,
+	Signal::SIGWINCH,
+];
+
+/// Forwards signals, which are sent to runh, to the attached process `pid`. On SIGWINCH, the
+/// terminal size is copied to the pty master instead. The forwarded signals are blocked in the
+/// calling thread and have to be unblocked with the returned set, when detaching again. This has
+/// to happen after the container process was spawned, as the signal mask is inherited.
+pub fn forward_signals(pid: Pid, master_fd: Option<RawFd>) -> SigSet {
+	let mut signals = SigSet::empty();
+	for signal in FORWARDED_SIGNALS {
+		signals.add(signal);
+	}
+	nix::sys::signal::pthread_sigmask(SigmaskHow::SIG_BLOCK, Some(&signals), None)
+		.expect("Could not block forwarded signals!");
+	std::thread::spawn(move || loop {
+		match signals.wait() {
+			Ok(Signal::SIGWINCH) => {
+				if let Some(master_fd) = master_fd {
+					console::copy_winsize(master_fd);
+				}
+			}
+			Ok(signal) => {
+				debug!("Forward signal {signal} to process {pid}");
+				let _ = nix::sys::signal::kill(pid, signal);
+			}
+			Err(err) => warn!("Could not wait for signals: {err}"),
+		}
+	});
+	signals
+}
@@ -1,4 +1,5 @@
 use crate::container::OCIContainer;
+use crate::hooks;
 use std::fs::{self, File};
 use std::io::Read;
 use std::path::PathBuf;
@@ -36,6 +37,14 @@ pub fn start_container(mut project_dir: PathBuf, id: &str) {
 				info!("Container started successfully! Deleting exec fifo!");
 				std::fs::remove_file(project_dir.join("exec.fifo"))
 					.expect("Could not delete exec fifo!");
+
+				if let Some(hooks) = container.spec().hooks().as_ref() {
+					let pid = std::fs::read_to_string(project_dir.join("created"))
+						.ok()
+						.and_then(|pid| pid.parse().ok());
+					let state = hooks::get_hook_state(&container, "running", pid);
+					hooks::run_hooks_lenient("poststart", hooks.poststart(), &state);
+				}
 			} else {
 				panic!(
 					"Invalid value read from fifo. Read byte was {:x}",
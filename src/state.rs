@@ -1,6 +1,18 @@
 use crate::{consts, container::OCIContainer};
 use serde::Serialize;
-use std::{collections::HashMap, fs::OpenOptions, io::BufReader, path::PathBuf};
+use std::io::{BufReader, Read};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::{collections::HashMap, fs::OpenOptions};
+
+/// Name of the file in the container directory, which marks a paused container. The pause state
+/// is recorded by runh pause and resume, so that querying the state does not need to contact the
+/// hypervisor, which may be busy with other clients.
+pub const PAUSED_FILE: &str = "paused";
+
+/// Name of the file in the root directory of a Hermit container, in which the supervisor of the
+/// VM records the exit code of the application
+pub const EXIT_CODE_FILE: &str = ".runh-exitcode";
 
 #[derive(Serialize, Debug)]
 pub struct State {
@@ -13,6 +25,37 @@ pub struct State {
 	pub bundle: String,
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub annotations: Option<HashMap<String, String>>,
+	/// Exit code of a stopped Hermit container, which is not part of the OCI state
+	#[serde(rename = "exitCode", default, skip_serializing_if = "Option::is_none")]
+	pub exit_code: Option<i32>,
+}
+
+/// Determines the rootfs of the container on the host.
+pub fn get_rootfs(container: &OCIContainer, bundle: &Path) -> PathBuf {
+	let root = container
+		.spec()
+		.root()
+		.as_ref()
+		.expect("Container spec does not contain a root!")
+		.path();
+	bundle.join(root)
+}
+
+/// Reads the exit code, which the supervisor of the VM recorded in the rootfs. As the file is
+/// controlled by the container, neither symbolic links nor anything else than a small regular
+/// file is accepted.
+fn read_exit_code(rootfs: &Path) -> Option<i32> {
+	let file = OpenOptions::new()
+		.read(true)
+		.custom_flags(libc::O_NOFOLLOW | libc::O_NONBLOCK)
+		.open(rootfs.join(EXIT_CODE_FILE))
+		.ok()?;
+	if !file.metadata().ok()?.is_file() {
+		return None;
+	}
+	let mut exit_code = String::new();
+	file.take(16).read_to_string(&mut exit_code).ok()?;
+	exit_code.trim().parse().ok()
 }
 
 pub fn get_container_state(project_dir: PathBuf, id: &str) -> Option<State> {
@@ -52,36 +95,47 @@ pub fn get_container_state(project_dir: PathBuf, id: &str) -> Option<State> {
 	let container: OCIContainer = serde_json::from_reader(BufReader::new(container_file))
 		.expect("Could not query state. Container file could not be parsed!");
 
-	Some(State {
-		version: consts::OCI_STATE_VERSION,
-		id: id.to_string(),
-		status: if let Some(pid_int) = pid {
-			if let Ok(process) = procfs::process::Process::new(pid_int) {
-				let process_state = process
-					.stat()
-					.expect("Could not query state. Process stat could not be read!")
-					.state()
-					.expect("Could not query state. Process state could not be read!");
-				match process_state {
-					procfs::process::ProcState::Zombie => "stopped",
-					procfs::process::ProcState::Dead => "stopped",
-					_ => {
-						if exec_fifo.exists() {
-							"created"
-						} else {
-							"running"
-						}
+	let status = if let Some(pid_int) = pid {
+		if let Ok(process) = procfs::process::Process::new(pid_int) {
+			let process_state = process
+				.stat()
+				.expect("Could not query state. Process stat could not be read!")
+				.state()
+				.expect("Could not query state. Process state could not be read!");
+			match process_state {
+				procfs::process::ProcState::Zombie => "stopped",
+				procfs::process::ProcState::Dead => "stopped",
+				_ => {
+					if exec_fifo.exists() {
+						"created"
+					} else if container_dir.join(PAUSED_FILE).exists() {
+						"paused"
+					} else {
+						"running"
 					}
 				}
-			} else {
-				"stopped"
 			}
 		} else {
-			"creating"
-		},
+			"stopped"
+		}
+	} else {
+		"creating"
+	};
+
+	let exit_code = if status == "stopped" && container.is_hermit_container() {
+		read_exit_code(&get_rootfs(&container, Path::new(&bundle)))
+	} else {
+		None
+	};
+
+	Some(State {
+		version: consts::OCI_STATE_VERSION,
+		id: id.to_string(),
+		status,
 		pid,
 		bundle,
 		annotations: container.spec().annotations().clone(),
+		exit_code,
 	})
 }
 
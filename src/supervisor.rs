@@ -0,0 +1,202 @@
+use crate::hypervisor::{self, Hypervisor};
+use crate::seccomp;
+use crate::signals::FORWARDED_SIGNALS;
+use crate::state;
+use crate::vmconfig::VmConfig;
+use capctl::prctl;
+use nix::errno::Errno;
+use nix::sys::signal::{SigSet, SigmaskHow, Signal};
+use nix::sys::wait::{WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use oci_spec::runtime::{
+	LinuxSeccompAction, LinuxSeccompArgBuilder, LinuxSeccompOperator, LinuxSyscallBuilder,
+};
+use std::convert::TryFrom;
+use std::ffi::{CString, OsStr};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Spawns the hypervisor and stays in the container as its parent. Signals are forwarded to the
+/// hypervisor and its exit status is translated into the exit code of the Hermit application,
+/// which becomes the exit code of the container. The exit code is recorded in the rootfs as well,
+/// so that it is reported by runh state.
+pub fn supervise_vm(mut cmd: Command, hypervisor: &dyn Hypervisor, vm_config: &VmConfig) -> ! {
+	let mut signals = SigSet::empty();
+	for signal in FORWARDED_SIGNALS {
+		signals.add(signal);
+	}
+	signals.add(Signal::SIGCHLD);
+	nix::sys::signal::sigprocmask(SigmaskHow::SIG_BLOCK, Some(&signals), None)
+		.expect("Could not block forwarded signals!");
+
+	// With a seccomp profile, the hypervisor is executed right after the filter is installed.
+	// execve is only allowed with the pointer to its path, which is not known to the hypervisor.
+	let to_cstring = |arg: &OsStr| CString::new(arg.as_bytes()).expect("Invalid exec argument!");
+	let program = to_cstring(cmd.get_program());
+	let argv: Vec<CString> = std::iter::once(program.clone())
+		.chain(cmd.get_args().map(to_cstring))
+		.collect();
+	// The hypervisor inherits the environment of runh init
+	let envp: Vec<CString> = std::env::vars_os()
+		.map(|(name, value)| {
+			let mut var = name;
+			var.push("=");
+			var.push(value);
+			to_cstring(&var)
+		})
+		.collect();
+	// Raw pointers are not Send, so that the null-terminated arrays only store the addresses
+	let to_pointers = |args: &[CString]| {
+		args.iter()
+			.map(|arg| arg.as_ptr() as usize)
+			.chain(std::iter::once(0))
+			.collect::<Vec<usize>>()
+	};
+	let argv_pointers = to_pointers(&argv);
+	let envp_pointers = to_pointers(&envp);
+
+	// The filter is compiled in advance, as the child must not allocate before exec
+	let mut filter = hypervisor.seccomp_profile(vm_config).map(|mut profile| {
+		let path = LinuxSeccompArgBuilder::default()
+			.index(0usize)
+			.value(program.as_ptr() as u64)
+			.op(LinuxSeccompOperator::ScmpCmpEq)
+			.build()
+			.unwrap();
+		let exec = LinuxSyscallBuilder::default()
+			.names(vec!["execve".to_string()])
+			.action(LinuxSeccompAction::ScmpActAllow)
+			.args(vec![path])
+			.build()
+			.unwrap();
+		let mut syscalls = profile.syscalls().clone().unwrap_or_default();
+		syscalls.push(exec);
+		profile.set_syscalls(Some(syscalls));
+		seccomp::compile_filter(&profile)
+	});
+
+	unsafe {
+		cmd.pre_exec(move || {
+			nix::sys::signal::sigprocmask(SigmaskHow::SIG_UNBLOCK, Some(&signals), None)?;
+			// Do not leave the VM behind, if the supervisor is killed
+			prctl::set_pdeathsig(Some(libc::SIGKILL))?;
+			if let Some(filter) = &mut filter {
+				seccomp::install_filter(filter)?;
+				// The arguments are kept alive by the closure
+				let _ = &argv;
+				let _ = &envp;
+				libc::execve(
+					program.as_ptr(),
+					argv_pointers.as_ptr() as *const *const libc::c_char,
+					envp_pointers.as_ptr() as *const *const libc::c_char,
+				);
+				return Err(std::io::Error::last_os_error());
+			}
+			Ok(())
+		});
+	}
+	// The child is reaped through waitpid below
+	#[allow(clippy::zombie_processes)]
+	let child = cmd.spawn().expect("Could not spawn hypervisor!");
+	let child_pid = Pid::from_raw(child.id() as i32);
+	debug!("Started hypervisor with PID {child_pid}");
+
+	// Exit codes are only decoded, once the VM was created. Before, the hypervisor failed to start.
+	let created = Arc::new(AtomicBool::new(false));
+	{
+		let created = created.clone();
+		let vm_config = vm_config.clone();
+		std::thread::spawn(move || {
+			let hypervisor = hypervisor::get_hypervisor(vm_config.hypervisor());
+			if !hypervisor.start_vm(&vm_config, &|| created.store(true, Ordering::SeqCst)) {
+				warn!("Could not start the VM. Killing the hypervisor...");
+				let _ = nix::sys::signal::kill(child_pid, Signal::SIGKILL);
+			}
+		});
+	}
+
+	// Deadline, after which the VM is killed, if it does not follow a shutdown request
+	let mut shutdown_deadline: Option<Instant> = None;
+
+	loop {
+		let timeout =
+			shutdown_deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+		match wait_for_signal(&signals, timeout) {
+			Ok(None) => {
+				warn!("Hermit application did not shut down in time. Killing the VM...");
+				let _ = nix::sys::signal::kill(child_pid, Signal::SIGKILL);
+				shutdown_deadline = None;
+			}
+			Ok(Some(Signal::SIGCHLD)) => {
+				// Reap all exited children, which also includes helpers like virtiofsd
+				while let Ok(status) = nix::sys::wait::waitpid(None, Some(WaitPidFlag::WNOHANG)) {
+					let exit_code = match status {
+						WaitStatus::Exited(pid, code) if pid == child_pid => {
+							if created.load(Ordering::SeqCst) {
+								hypervisor.decode_exit_code(vm_config, code)
+							} else {
+								warn!("Hypervisor exited with status {code}, before the VM was created");
+								code.max(1)
+							}
+						}
+						WaitStatus::Signaled(pid, signal, _) if pid == child_pid => {
+							128 + signal as i32
+						}
+						WaitStatus::StillAlive => break,
+						_ => continue,
+					};
+					info!("Hermit application exited with exit code {exit_code}");
+					record_exit_code(exit_code);
+					std::process::exit(exit_code);
+				}
+			}
+			Ok(Some(Signal::SIGTERM))
+				if shutdown_deadline.is_none() && hypervisor.request_shutdown(vm_config) =>
+			{
+				// Give the unikernel the chance to shut down cleanly instead of terminating the hypervisor
+				info!(
+					"Requested shutdown of the Hermit application, waiting up to {}s",
+					vm_config.shutdown_grace_period()
+				);
+				shutdown_deadline =
+					Some(Instant::now() + Duration::from_secs(vm_config.shutdown_grace_period()));
+			}
+			Ok(Some(signal)) => {
+				debug!("Forward signal {signal} to hypervisor");
+				let _ = nix::sys::signal::kill(child_pid, signal);
+			}
+			Err(err) => warn!("Could not wait for signals: {err}"),
+		}
+	}
+}
+
+/// Writes the exit code of the Hermit application into the root directory of the container.
+fn record_exit_code(exit_code: i32) {
+	let path = Path::new("/").join(state::EXIT_CODE_FILE);
+	if let Err(err) = std::fs::write(path, exit_code.to_string()) {
+		warn!("Could not record exit code of the Hermit application: {err}");
+	}
+}
+
+/// Waits for one of the blocked `signals`. Returns `None`, if the timeout expired.
+fn wait_for_signal(signals: &SigSet, timeout: Option<Duration>) -> nix::Result<Option<Signal>> {
+	let Some(timeout) = timeout else {
+		return signals.wait().map(Some);
+	};
+
+	let timeout = libc::timespec {
+		tv_sec: timeout.as_secs() as libc::time_t,
+		tv_nsec: timeout.subsec_nanos() as libc::c_long,
+	};
+	let res = unsafe { libc::sigtimedwait(signals.as_ref(), std::ptr::null_mut(), &timeout) };
+	match Errno::result(res) {
+		Ok(signal) => Signal::try_from(signal).map(Some),
+		Err(Errno::EAGAIN) => Ok(None),
+		Err(err) => Err(err),
+	}
+}
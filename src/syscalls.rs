@@ -0,0 +1,1896 @@
+//! System call numbers of the architectures supported by runh, which are required to compile
+//! seccomp filters from the names used by the OCI runtime specification. The numbers are taken
+//! from the constants `libc::SYS_*`. System calls, which are not yet known to libc, and the
+//! system calls of compat architectures, which are not covered by libc, follow the uapi headers
+//! of Linux 6.12.
+
+use oci_spec::runtime::Arch;
+
+/// System call names and numbers of an architecture
+pub type SyscallTable = &'static [(&'static str, u32)];
+
+/// System calls of the x32 ABI share the architecture with x86_64, but have this bit set
+#[cfg(target_arch = "x86_64")]
+pub const X32_SYSCALL_BIT: u32 = 0x40000000;
+
+/// Removes the prefix `SYS_` from the name of a libc constant
+const fn syscall_name(constant: &'static str) -> &'static str {
+	constant.split_at(4).1
+}
+
+/// Builds the table of system call names and numbers from the given libc constants, followed by
+/// the system calls, which are missing in libc, with their numbers.
+macro_rules! syscalls {
+	($($constant:ident,)* $(; $($name:ident = $nr:literal,)*)?) => {
+		&[
+			$((syscall_name(stringify!($constant)), libc::$constant as u32),)*
+			$($((stringify!($name), $nr),)*)?
+		]
+	};
+}
+
+// The module system calls of Linux 2.4 are deprecated in libc, but still part of the table
+#[cfg(target_arch = "x86_64")]
+#[allow(deprecated)]
+pub const SYSCALLS: SyscallTable = syscalls!(
+	SYS_accept,
+	SYS_accept4,
+	SYS_access,
+	SYS_acct,
+	SYS_add_key,
+	SYS_adjtimex,
+	SYS_afs_syscall,
+	SYS_alarm,
+	SYS_arch_prctl,
+	SYS_bind,
+	SYS_bpf,
+	SYS_brk,
+	SYS_capget,
+	SYS_capset,
+	SYS_chdir,
+	SYS_chmod,
+	SYS_chown,
+	SYS_chroot,
+	SYS_clock_adjtime,
+	SYS_clock_getres,
+	SYS_clock_gettime,
+	SYS_clock_nanosleep,
+	SYS_clock_settime,
+	SYS_clone,
+	SYS_clone3,
+	SYS_close,
+	SYS_close_range,
+	SYS_connect,
+	SYS_copy_file_range,
+	SYS_creat,
+	SYS_create_module,
+	SYS_delete_module,
+	SYS_dup,
+	SYS_dup2,
+	SYS_dup3,
+	SYS_epoll_create,
+	SYS_epoll_create1,
+	SYS_epoll_ctl,
+	SYS_epoll_ctl_old,
+	SYS_epoll_pwait,
+	SYS_epoll_pwait2,
+	SYS_epoll_wait,
+	SYS_epoll_wait_old,
+	SYS_eventfd,
+	SYS_eventfd2,
+	SYS_execve,
+	SYS_execveat,
+	SYS_exit,
+	SYS_exit_group,
+	SYS_faccessat,
+	SYS_faccessat2,
+	SYS_fadvise64,
+	SYS_fallocate,
+	SYS_fanotify_init,
+	SYS_fanotify_mark,
+	SYS_fchdir,
+	SYS_fchmod,
+	SYS_fchmodat,
+	SYS_fchmodat2,
+	SYS_fchown,
+	SYS_fchownat,
+	SYS_fcntl,
+	SYS_fdatasync,
+	SYS_fgetxattr,
+	SYS_finit_module,
+	SYS_flistxattr,
+	SYS_flock,
+	SYS_fork,
+	SYS_fremovexattr,
+	SYS_fsconfig,
+	SYS_fsetxattr,
+	SYS_fsmount,
+	SYS_fsopen,
+	SYS_fspick,
+	SYS_fstat,
+	SYS_fstatfs,
+	SYS_fsync,
+	SYS_ftruncate,
+	SYS_futex,
+	SYS_futex_waitv,
+	SYS_futimesat,
+	SYS_getcpu,
+	SYS_getcwd,
+	SYS_getdents,
+	SYS_getdents64,
+	SYS_getegid,
+	SYS_geteuid,
+	SYS_getgid,
+	SYS_getgroups,
+	SYS_getitimer,
+	SYS_get_kernel_syms,
+	SYS_get_mempolicy,
+	SYS_getpeername,
+	SYS_getpgid,
+	SYS_getpgrp,
+	SYS_getpid,
+	SYS_getpmsg,
+	SYS_getppid,
+	SYS_getpriority,
+	SYS_getrandom,
+	SYS_getresgid,
+	SYS_getresuid,
+	SYS_getrlimit,
+	SYS_get_robust_list,
+	SYS_getrusage,
+	SYS_getsid,
+	SYS_getsockname,
+	SYS_getsockopt,
+	SYS_get_thread_area,
+	SYS_gettid,
+	SYS_gettimeofday,
+	SYS_getuid,
+	SYS_getxattr,
+	SYS_init_module,
+	SYS_inotify_add_watch,
+	SYS_inotify_init,
+	SYS_inotify_init1,
+	SYS_inotify_rm_watch,
+	SYS_io_cancel,
+	SYS_ioctl,
+	SYS_io_destroy,
+	SYS_io_getevents,
+	SYS_ioperm,
+	SYS_iopl,
+	SYS_ioprio_get,
+	SYS_ioprio_set,
+	SYS_io_setup,
+	SYS_io_submit,
+	SYS_io_uring_enter,
+	SYS_io_uring_register,
+	SYS_io_uring_setup,
+	SYS_kcmp,
+	SYS_kexec_file_load,
+	SYS_kexec_load,
+	SYS_keyctl,
+	SYS_kill,
+	SYS_landlock_add_rule,
+	SYS_landlock_create_ruleset,
+	SYS_landlock_restrict_self,
+	SYS_lchown,
+	SYS_lgetxattr,
+	SYS_link,
+	SYS_linkat,
+	SYS_listen,
+	SYS_listxattr,
+	SYS_llistxattr,
+	SYS_lookup_dcookie,
+	SYS_lremovexattr,
+	SYS_lseek,
+	SYS_lsetxattr,
+	SYS_lstat,
+	SYS_madvise,
+	SYS_mbind,
+	SYS_membarrier,
+	SYS_memfd_create,
+	SYS_memfd_secret,
+	SYS_migrate_pages,
+	SYS_mincore,
+	SYS_mkdir,
+	SYS_mkdirat,
+	SYS_mknod,
+	SYS_mknodat,
+	SYS_mlock,
+	SYS_mlock2,
+	SYS_mlockall,
+	SYS_mmap,
+	SYS_modify_ldt,
+	SYS_mount,
+	SYS_mount_setattr,
+	SYS_move_mount,
+	SYS_move_pages,
+	SYS_mprotect,
+	SYS_mq_getsetattr,
+	SYS_mq_notify,
+	SYS_mq_open,
+	SYS_mq_timedreceive,
+	SYS_mq_timedsend,
+	SYS_mq_unlink,
+	SYS_mremap,
+	SYS_mseal,
+	SYS_msgctl,
+	SYS_msgget,
+	SYS_msgrcv,
+	SYS_msgsnd,
+	SYS_msync,
+	SYS_munlock,
+	SYS_munlockall,
+	SYS_munmap,
+	SYS_name_to_handle_at,
+	SYS_nanosleep,
+	SYS_newfstatat,
+	SYS_nfsservctl,
+	SYS_open,
+	SYS_openat,
+	SYS_openat2,
+	SYS_open_by_handle_at,
+	SYS_open_tree,
+	SYS_pause,
+	SYS_perf_event_open,
+	SYS_personality,
+	SYS_pidfd_getfd,
+	SYS_pidfd_open,
+	SYS_pidfd_send_signal,
+	SYS_pipe,
+	SYS_pipe2,
+	SYS_pivot_root,
+	SYS_pkey_alloc,
+	SYS_pkey_free,
+	SYS_pkey_mprotect,
+	SYS_poll,
+	SYS_ppoll,
+	SYS_prctl,
+	SYS_pread64,
+	SYS_preadv,
+	SYS_preadv2,
+	SYS_prlimit64,
+	SYS_process_madvise,
+	SYS_process_mrelease,
+	SYS_process_vm_readv,
+	SYS_process_vm_writev,
+	SYS_pselect6,
+	SYS_ptrace,
+	SYS_putpmsg,
+	SYS_pwrite64,
+	SYS_pwritev,
+	SYS_pwritev2,
+	SYS_query_module,
+	SYS_quotactl,
+	SYS_quotactl_fd,
+	SYS_read,
+	SYS_readahead,
+	SYS_readlink,
+	SYS_readlinkat,
+	SYS_readv,
+	SYS_reboot,
+	SYS_recvfrom,
+	SYS_recvmmsg,
+	SYS_recvmsg,
+	SYS_remap_file_pages,
+	SYS_removexattr,
+	SYS_rename,
+	SYS_renameat,
+	SYS_renameat2,
+	SYS_request_key,
+	SYS_restart_syscall,
+	SYS_rmdir,
+	SYS_rseq,
+	SYS_rt_sigaction,
+	SYS_rt_sigpending,
+	SYS_rt_sigprocmask,
+	SYS_rt_sigqueueinfo,
+	SYS_rt_sigreturn,
+	SYS_rt_sigsuspend,
+	SYS_rt_sigtimedwait,
+	SYS_rt_tgsigqueueinfo,
+	SYS_sched_getaffinity,
+	SYS_sched_getattr,
+	SYS_sched_getparam,
+	SYS_sched_get_priority_max,
+	SYS_sched_get_priority_min,
+	SYS_sched_getscheduler,
+	SYS_sched_rr_get_interval,
+	SYS_sched_setaffinity,
+	SYS_sched_setattr,
+	SYS_sched_setparam,
+	SYS_sched_setscheduler,
+	SYS_sched_yield,
+	SYS_seccomp,
+	SYS_security,
+	SYS_select,
+	SYS_semctl,
+	SYS_semget,
+	SYS_semop,
+	SYS_semtimedop,
+	SYS_sendfile,
+	SYS_sendmmsg,
+	SYS_sendmsg,
+	SYS_sendto,
+	SYS_setdomainname,
+	SYS_setfsgid,
+	SYS_setfsuid,
+	SYS_setgid,
+	SYS_setgroups,
+	SYS_sethostname,
+	SYS_setitimer,
+	SYS_set_mempolicy,
+	SYS_set_mempolicy_home_node,
+	SYS_setns,
+	SYS_setpgid,
+	SYS_setpriority,
+	SYS_setregid,
+	SYS_setresgid,
+	SYS_setresuid,
+	SYS_setreuid,
+	SYS_setrlimit,
+	SYS_set_robust_list,
+	SYS_setsid,
+	SYS_setsockopt,
+	SYS_set_thread_area,
+	SYS_set_tid_address,
+	SYS_settimeofday,
+	SYS_setuid,
+	SYS_setxattr,
+	SYS_shmat,
+	SYS_shmctl,
+	SYS_shmdt,
+	SYS_shmget,
+	SYS_shutdown,
+	SYS_sigaltstack,
+	SYS_signalfd,
+	SYS_signalfd4,
+	SYS_socket,
+	SYS_socketpair,
+	SYS_splice,
+	SYS_stat,
+	SYS_statfs,
+	SYS_statx,
+	SYS_swapoff,
+	SYS_swapon,
+	SYS_symlink,
+	SYS_symlinkat,
+	SYS_sync,
+	SYS_sync_file_range,
+	SYS_syncfs,
+	SYS__sysctl,
+	SYS_sysfs,
+	SYS_sysinfo,
+	SYS_syslog,
+	SYS_tee,
+	SYS_tgkill,
+	SYS_time,
+	SYS_timer_create,
+	SYS_timer_delete,
+	SYS_timerfd_create,
+	SYS_timerfd_gettime,
+	SYS_timerfd_settime,
+	SYS_timer_getoverrun,
+	SYS_timer_gettime,
+	SYS_timer_settime,
+	SYS_times,
+	SYS_tkill,
+	SYS_truncate,
+	SYS_tuxcall,
+	SYS_umask,
+	SYS_umount2,
+	SYS_uname,
+	SYS_unlink,
+	SYS_unlinkat,
+	SYS_unshare,
+	SYS_uselib,
+	SYS_userfaultfd,
+	SYS_ustat,
+	SYS_utime,
+	SYS_utimensat,
+	SYS_utimes,
+	SYS_vfork,
+	SYS_vhangup,
+	SYS_vmsplice,
+	SYS_vserver,
+	SYS_wait4,
+	SYS_waitid,
+	SYS_write,
+	SYS_writev,
+	;
+	cachestat = 451,
+	futex_requeue = 456,
+	futex_wait = 455,
+	futex_wake = 454,
+	io_pgetevents = 333,
+	listmount = 458,
+	lsm_get_self_attr = 459,
+	lsm_list_modules = 461,
+	lsm_set_self_attr = 460,
+	map_shadow_stack = 453,
+	statmount = 457,
+	uretprobe = 335,
+);
+
+#[cfg(target_arch = "aarch64")]
+pub const SYSCALLS: SyscallTable = syscalls!(
+	SYS_accept,
+	SYS_accept4,
+	SYS_acct,
+	SYS_add_key,
+	SYS_adjtimex,
+	SYS_bind,
+	SYS_bpf,
+	SYS_brk,
+	SYS_capget,
+	SYS_capset,
+	SYS_chdir,
+	SYS_chroot,
+	SYS_clock_adjtime,
+	SYS_clock_getres,
+	SYS_clock_gettime,
+	SYS_clock_nanosleep,
+	SYS_clock_settime,
+	SYS_clone,
+	SYS_clone3,
+	SYS_close,
+	SYS_close_range,
+	SYS_connect,
+	SYS_copy_file_range,
+	SYS_delete_module,
+	SYS_dup,
+	SYS_dup3,
+	SYS_epoll_create1,
+	SYS_epoll_ctl,
+	SYS_epoll_pwait,
+	SYS_epoll_pwait2,
+	SYS_eventfd2,
+	SYS_execve,
+	SYS_execveat,
+	SYS_exit,
+	SYS_exit_group,
+	SYS_faccessat,
+	SYS_faccessat2,
+	SYS_fadvise64,
+	SYS_fallocate,
+	SYS_fanotify_init,
+	SYS_fanotify_mark,
+	SYS_fchdir,
+	SYS_fchmod,
+	SYS_fchmodat,
+	SYS_fchown,
+	SYS_fchownat,
+	SYS_fcntl,
+	SYS_fdatasync,
+	SYS_fgetxattr,
+	SYS_finit_module,
+	SYS_flistxattr,
+	SYS_flock,
+	SYS_fremovexattr,
+	SYS_fsconfig,
+	SYS_fsetxattr,
+	SYS_fsmount,
+	SYS_fsopen,
+	SYS_fspick,
+	SYS_fstat,
+	SYS_fstatfs,
+	SYS_fsync,
+	SYS_ftruncate,
+	SYS_futex,
+	SYS_futex_waitv,
+	SYS_getcpu,
+	SYS_getcwd,
+	SYS_getdents64,
+	SYS_getegid,
+	SYS_geteuid,
+	SYS_getgid,
+	SYS_getgroups,
+	SYS_getitimer,
+	SYS_get_mempolicy,
+	SYS_getpeername,
+	SYS_getpgid,
+	SYS_getpid,
+	SYS_getppid,
+	SYS_getpriority,
+	SYS_getrandom,
+	SYS_getresgid,
+	SYS_getresuid,
+	SYS_getrlimit,
+	SYS_get_robust_list,
+	SYS_getrusage,
+	SYS_getsid,
+	SYS_getsockname,
+	SYS_getsockopt,
+	SYS_gettid,
+	SYS_gettimeofday,
+	SYS_getuid,
+	SYS_getxattr,
+	SYS_init_module,
+	SYS_inotify_add_watch,
+	SYS_inotify_init1,
+	SYS_inotify_rm_watch,
+	SYS_io_cancel,
+	SYS_ioctl,
+	SYS_io_destroy,
+	SYS_io_getevents,
+	SYS_ioprio_get,
+	SYS_ioprio_set,
+	SYS_io_setup,
+	SYS_io_submit,
+	SYS_io_uring_enter,
+	SYS_io_uring_register,
+	SYS_io_uring_setup,
+	SYS_kcmp,
+	SYS_kexec_file_load,
+	SYS_kexec_load,
+	SYS_keyctl,
+	SYS_kill,
+	SYS_landlock_add_rule,
+	SYS_landlock_create_ruleset,
+	SYS_landlock_restrict_self,
+	SYS_lgetxattr,
+	SYS_linkat,
+	SYS_listen,
+	SYS_listxattr,
+	SYS_llistxattr,
+	SYS_lookup_dcookie,
+	SYS_lremovexattr,
+	SYS_lseek,
+	SYS_lsetxattr,
+	SYS_madvise,
+	SYS_mbind,
+	SYS_membarrier,
+	SYS_memfd_create,
+	SYS_memfd_secret,
+	SYS_migrate_pages,
+	SYS_mincore,
+	SYS_mkdirat,
+	SYS_mknodat,
+	SYS_mlock,
+	SYS_mlock2,
+	SYS_mlockall,
+	SYS_mmap,
+	SYS_mount,
+	SYS_mount_setattr,
+	SYS_move_mount,
+	SYS_move_pages,
+	SYS_mprotect,
+	SYS_mq_getsetattr,
+	SYS_mq_notify,
+	SYS_mq_open,
+	SYS_mq_timedreceive,
+	SYS_mq_timedsend,
+	SYS_mq_unlink,
+	SYS_mremap,
+	SYS_mseal,
+	SYS_msgctl,
+	SYS_msgget,
+	SYS_msgrcv,
+	SYS_msgsnd,
+	SYS_msync,
+	SYS_munlock,
+	SYS_munlockall,
+	SYS_munmap,
+	SYS_name_to_handle_at,
+	SYS_nanosleep,
+	SYS_newfstatat,
+	SYS_nfsservctl,
+	SYS_openat,
+	SYS_openat2,
+	SYS_open_by_handle_at,
+	SYS_open_tree,
+	SYS_perf_event_open,
+	SYS_personality,
+	SYS_pidfd_getfd,
+	SYS_pidfd_open,
+	SYS_pidfd_send_signal,
+	SYS_pipe2,
+	SYS_pivot_root,
+	SYS_pkey_alloc,
+	SYS_pkey_free,
+	SYS_pkey_mprotect,
+	SYS_ppoll,
+	SYS_prctl,
+	SYS_pread64,
+	SYS_preadv,
+	SYS_preadv2,
+	SYS_prlimit64,
+	SYS_process_madvise,
+	SYS_process_mrelease,
+	SYS_process_vm_readv,
+	SYS_process_vm_writev,
+	SYS_pselect6,
+	SYS_ptrace,
+	SYS_pwrite64,
+	SYS_pwritev,
+	SYS_pwritev2,
+	SYS_quotactl,
+	SYS_quotactl_fd,
+	SYS_read,
+	SYS_readahead,
+	SYS_readlinkat,
+	SYS_readv,
+	SYS_reboot,
+	SYS_recvfrom,
+	SYS_recvmmsg,
+	SYS_recvmsg,
+	SYS_remap_file_pages,
+	SYS_removexattr,
+	SYS_renameat,
+	SYS_renameat2,
+	SYS_request_key,
+	SYS_restart_syscall,
+	SYS_rseq,
+	SYS_rt_sigaction,
+	SYS_rt_sigpending,
+	SYS_rt_sigprocmask,
+	SYS_rt_sigqueueinfo,
+	SYS_rt_sigreturn,
+	SYS_rt_sigsuspend,
+	SYS_rt_sigtimedwait,
+	SYS_rt_tgsigqueueinfo,
+	SYS_sched_getaffinity,
+	SYS_sched_getattr,
+	SYS_sched_getparam,
+	SYS_sched_get_priority_max,
+	SYS_sched_get_priority_min,
+	SYS_sched_getscheduler,
+	SYS_sched_rr_get_interval,
+	SYS_sched_setaffinity,
+	SYS_sched_setattr,
+	SYS_sched_setparam,
+	SYS_sched_setscheduler,
+	SYS_sched_yield,
+	SYS_seccomp,
+	SYS_semctl,
+	SYS_semget,
+	SYS_semop,
+	SYS_semtimedop,
+	SYS_sendfile,
+	SYS_sendmmsg,
+	SYS_sendmsg,
+	SYS_sendto,
+	SYS_setdomainname,
+	SYS_setfsgid,
+	SYS_setfsuid,
+	SYS_setgid,
+	SYS_setgroups,
+	SYS_sethostname,
+	SYS_setitimer,
+	SYS_set_mempolicy,
+	SYS_set_mempolicy_home_node,
+	SYS_setns,
+	SYS_setpgid,
+	SYS_setpriority,
+	SYS_setregid,
+	SYS_setresgid,
+	SYS_setresuid,
+	SYS_setreuid,
+	SYS_setrlimit,
+	SYS_set_robust_list,
+	SYS_setsid,
+	SYS_setsockopt,
+	SYS_set_tid_address,
+	SYS_settimeofday,
+	SYS_setuid,
+	SYS_setxattr,
+	SYS_shmat,
+	SYS_shmctl,
+	SYS_shmdt,
+	SYS_shmget,
+	SYS_shutdown,
+	SYS_sigaltstack,
+	SYS_signalfd4,
+	SYS_socket,
+	SYS_socketpair,
+	SYS_splice,
+	SYS_statfs,
+	SYS_statx,
+	SYS_swapoff,
+	SYS_swapon,
+	SYS_symlinkat,
+	SYS_sync,
+	SYS_sync_file_range,
+	SYS_syncfs,
+	SYS_sysinfo,
+	SYS_syslog,
+	SYS_tee,
+	SYS_tgkill,
+	SYS_timer_create,
+	SYS_timer_delete,
+	SYS_timerfd_create,
+	SYS_timerfd_gettime,
+	SYS_timerfd_settime,
+	SYS_timer_getoverrun,
+	SYS_timer_gettime,
+	SYS_timer_settime,
+	SYS_times,
+	SYS_tkill,
+	SYS_truncate,
+	SYS_umask,
+	SYS_umount2,
+	SYS_uname,
+	SYS_unlinkat,
+	SYS_unshare,
+	SYS_userfaultfd,
+	SYS_utimensat,
+	SYS_vhangup,
+	SYS_vmsplice,
+	SYS_wait4,
+	SYS_waitid,
+	SYS_write,
+	SYS_writev,
+	;
+	cachestat = 451,
+	fchmodat2 = 452,
+	futex_requeue = 456,
+	futex_wait = 455,
+	futex_wake = 454,
+	io_pgetevents = 292,
+	listmount = 458,
+	lsm_get_self_attr = 459,
+	lsm_list_modules = 461,
+	lsm_set_self_attr = 460,
+	map_shadow_stack = 453,
+	statmount = 457,
+);
+
+#[cfg(target_arch = "riscv64")]
+pub const SYSCALLS: SyscallTable = syscalls!(
+	SYS_accept,
+	SYS_accept4,
+	SYS_acct,
+	SYS_add_key,
+	SYS_adjtimex,
+	SYS_bind,
+	SYS_bpf,
+	SYS_brk,
+	SYS_capget,
+	SYS_capset,
+	SYS_chdir,
+	SYS_chroot,
+	SYS_clock_adjtime,
+	SYS_clock_getres,
+	SYS_clock_gettime,
+	SYS_clock_nanosleep,
+	SYS_clock_settime,
+	SYS_clone,
+	SYS_clone3,
+	SYS_close,
+	SYS_close_range,
+	SYS_connect,
+	SYS_copy_file_range,
+	SYS_delete_module,
+	SYS_dup,
+	SYS_dup3,
+	SYS_epoll_create1,
+	SYS_epoll_ctl,
+	SYS_epoll_pwait,
+	SYS_epoll_pwait2,
+	SYS_eventfd2,
+	SYS_execve,
+	SYS_execveat,
+	SYS_exit,
+	SYS_exit_group,
+	SYS_faccessat,
+	SYS_faccessat2,
+	SYS_fadvise64,
+	SYS_fallocate,
+	SYS_fanotify_init,
+	SYS_fanotify_mark,
+	SYS_fchdir,
+	SYS_fchmod,
+	SYS_fchmodat,
+	SYS_fchown,
+	SYS_fchownat,
+	SYS_fcntl,
+	SYS_fdatasync,
+	SYS_fgetxattr,
+	SYS_finit_module,
+	SYS_flistxattr,
+	SYS_flock,
+	SYS_fremovexattr,
+	SYS_fsconfig,
+	SYS_fsetxattr,
+	SYS_fsmount,
+	SYS_fsopen,
+	SYS_fspick,
+	SYS_fstat,
+	SYS_fstatfs,
+	SYS_fsync,
+	SYS_ftruncate,
+	SYS_futex,
+	SYS_futex_waitv,
+	SYS_getcpu,
+	SYS_getcwd,
+	SYS_getdents64,
+	SYS_getegid,
+	SYS_geteuid,
+	SYS_getgid,
+	SYS_getgroups,
+	SYS_getitimer,
+	SYS_get_mempolicy,
+	SYS_getpeername,
+	SYS_getpgid,
+	SYS_getpid,
+	SYS_getppid,
+	SYS_getpriority,
+	SYS_getrandom,
+	SYS_getresgid,
+	SYS_getresuid,
+	SYS_getrlimit,
+	SYS_get_robust_list,
+	SYS_getrusage,
+	SYS_getsid,
+	SYS_getsockname,
+	SYS_getsockopt,
+	SYS_gettid,
+	SYS_gettimeofday,
+	SYS_getuid,
+	SYS_getxattr,
+	SYS_init_module,
+	SYS_inotify_add_watch,
+	SYS_inotify_init1,
+	SYS_inotify_rm_watch,
+	SYS_io_cancel,
+	SYS_ioctl,
+	SYS_io_destroy,
+	SYS_io_getevents,
+	SYS_ioprio_get,
+	SYS_ioprio_set,
+	SYS_io_setup,
+	SYS_io_submit,
+	SYS_io_uring_enter,
+	SYS_io_uring_register,
+	SYS_io_uring_setup,
+	SYS_kcmp,
+	SYS_kexec_load,
+	SYS_keyctl,
+	SYS_kill,
+	SYS_landlock_add_rule,
+	SYS_landlock_create_ruleset,
+	SYS_landlock_restrict_self,
+	SYS_lgetxattr,
+	SYS_linkat,
+	SYS_listen,
+	SYS_listxattr,
+	SYS_llistxattr,
+	SYS_lookup_dcookie,
+	SYS_lremovexattr,
+	SYS_lseek,
+	SYS_lsetxattr,
+	SYS_madvise,
+	SYS_mbind,
+	SYS_membarrier,
+	SYS_memfd_create,
+	SYS_memfd_secret,
+	SYS_migrate_pages,
+	SYS_mincore,
+	SYS_mkdirat,
+	SYS_mknodat,
+	SYS_mlock,
+	SYS_mlock2,
+	SYS_mlockall,
+	SYS_mmap,
+	SYS_mount,
+	SYS_mount_setattr,
+	SYS_move_mount,
+	SYS_move_pages,
+	SYS_mprotect,
+	SYS_mq_getsetattr,
+	SYS_mq_notify,
+	SYS_mq_open,
+	SYS_mq_timedreceive,
+	SYS_mq_timedsend,
+	SYS_mq_unlink,
+	SYS_mremap,
+	SYS_msgctl,
+	SYS_msgget,
+	SYS_msgrcv,
+	SYS_msgsnd,
+	SYS_msync,
+	SYS_munlock,
+	SYS_munlockall,
+	SYS_munmap,
+	SYS_name_to_handle_at,
+	SYS_nanosleep,
+	SYS_newfstatat,
+	SYS_nfsservctl,
+	SYS_openat,
+	SYS_openat2,
+	SYS_open_by_handle_at,
+	SYS_open_tree,
+	SYS_perf_event_open,
+	SYS_personality,
+	SYS_pidfd_getfd,
+	SYS_pidfd_open,
+	SYS_pidfd_send_signal,
+	SYS_pipe2,
+	SYS_pivot_root,
+	SYS_pkey_alloc,
+	SYS_pkey_free,
+	SYS_pkey_mprotect,
+	SYS_ppoll,
+	SYS_prctl,
+	SYS_pread64,
+	SYS_preadv,
+	SYS_preadv2,
+	SYS_prlimit64,
+	SYS_process_madvise,
+	SYS_process_mrelease,
+	SYS_process_vm_readv,
+	SYS_process_vm_writev,
+	SYS_pselect6,
+	SYS_ptrace,
+	SYS_pwrite64,
+	SYS_pwritev,
+	SYS_pwritev2,
+	SYS_quotactl,
+	SYS_quotactl_fd,
+	SYS_read,
+	SYS_readahead,
+	SYS_readlinkat,
+	SYS_readv,
+	SYS_reboot,
+	SYS_recvfrom,
+	SYS_recvmmsg,
+	SYS_recvmsg,
+	SYS_remap_file_pages,
+	SYS_removexattr,
+	SYS_renameat2,
+	SYS_request_key,
+	SYS_restart_syscall,
+	SYS_rseq,
+	SYS_rt_sigaction,
+	SYS_rt_sigpending,
+	SYS_rt_sigprocmask,
+	SYS_rt_sigqueueinfo,
+	SYS_rt_sigreturn,
+	SYS_rt_sigsuspend,
+	SYS_rt_sigtimedwait,
+	SYS_rt_tgsigqueueinfo,
+	SYS_sched_getaffinity,
+	SYS_sched_getattr,
+	SYS_sched_getparam,
+	SYS_sched_get_priority_max,
+	SYS_sched_get_priority_min,
+	SYS_sched_getscheduler,
+	SYS_sched_rr_get_interval,
+	SYS_sched_setaffinity,
+	SYS_sched_setattr,
+	SYS_sched_setparam,
+	SYS_sched_setscheduler,
+	SYS_sched_yield,
+	SYS_seccomp,
+	SYS_semctl,
+	SYS_semget,
+	SYS_semop,
+	SYS_semtimedop,
+	SYS_sendfile,
+	SYS_sendmmsg,
+	SYS_sendmsg,
+	SYS_sendto,
+	SYS_setdomainname,
+	SYS_setfsgid,
+	SYS_setfsuid,
+	SYS_setgid,
+	SYS_setgroups,
+	SYS_sethostname,
+	SYS_setitimer,
+	SYS_set_mempolicy,
+	SYS_set_mempolicy_home_node,
+	SYS_setns,
+	SYS_setpgid,
+	SYS_setpriority,
+	SYS_setregid,
+	SYS_setresgid,
+	SYS_setresuid,
+	SYS_setreuid,
+	SYS_setrlimit,
+	SYS_set_robust_list,
+	SYS_setsid,
+	SYS_setsockopt,
+	SYS_set_tid_address,
+	SYS_settimeofday,
+	SYS_setuid,
+	SYS_setxattr,
+	SYS_shmat,
+	SYS_shmctl,
+	SYS_shmdt,
+	SYS_shmget,
+	SYS_shutdown,
+	SYS_sigaltstack,
+	SYS_signalfd4,
+	SYS_socket,
+	SYS_socketpair,
+	SYS_splice,
+	SYS_statfs,
+	SYS_statx,
+	SYS_swapoff,
+	SYS_swapon,
+	SYS_symlinkat,
+	SYS_sync,
+	SYS_sync_file_range,
+	SYS_syncfs,
+	SYS_sysinfo,
+	SYS_syslog,
+	SYS_tee,
+	SYS_tgkill,
+	SYS_timer_create,
+	SYS_timer_delete,
+	SYS_timerfd_create,
+	SYS_timerfd_gettime,
+	SYS_timerfd_settime,
+	SYS_timer_getoverrun,
+	SYS_timer_gettime,
+	SYS_timer_settime,
+	SYS_times,
+	SYS_tkill,
+	SYS_truncate,
+	SYS_umask,
+	SYS_umount2,
+	SYS_uname,
+	SYS_unlinkat,
+	SYS_unshare,
+	SYS_userfaultfd,
+	SYS_utimensat,
+	SYS_vhangup,
+	SYS_vmsplice,
+	SYS_wait4,
+	SYS_waitid,
+	SYS_write,
+	SYS_writev,
+	;
+	cachestat = 451,
+	fchmodat2 = 452,
+	futex_requeue = 456,
+	futex_wait = 455,
+	futex_wake = 454,
+	io_pgetevents = 292,
+	kexec_file_load = 294,
+	listmount = 458,
+	lsm_get_self_attr = 459,
+	lsm_list_modules = 461,
+	lsm_set_self_attr = 460,
+	map_shadow_stack = 453,
+	mseal = 462,
+	riscv_flush_icache = 259,
+	riscv_hwprobe = 258,
+	statmount = 457,
+);
+
+/// System calls of i386 applications, which are able to run on x86_64 hosts
+#[cfg(target_arch = "x86_64")]
+pub const SYSCALLS_X86: SyscallTable = &[
+	("_llseek", 140),
+	("_newselect", 142),
+	("_sysctl", 149),
+	("accept4", 364),
+	("access", 33),
+	("acct", 51),
+	("add_key", 286),
+	("adjtimex", 124),
+	("afs_syscall", 137),
+	("alarm", 27),
+	("arch_prctl", 384),
+	("bdflush", 134),
+	("bind", 361),
+	("bpf", 357),
+	("break", 17),
+	("brk", 45),
+	("cachestat", 451),
+	("capget", 184),
+	("capset", 185),
+	("chdir", 12),
+	("chmod", 15),
+	("chown", 182),
+	("chown32", 212),
+	("chroot", 61),
+	("clock_adjtime", 343),
+	("clock_adjtime64", 405),
+	("clock_getres", 266),
+	("clock_getres_time64", 406),
+	("clock_gettime", 265),
+	("clock_gettime64", 403),
+	("clock_nanosleep", 267),
+	("clock_nanosleep_time64", 407),
+	("clock_settime", 264),
+	("clock_settime64", 404),
+	("clone", 120),
+	("clone3", 435),
+	("close", 6),
+	("close_range", 436),
+	("connect", 362),
+	("copy_file_range", 377),
+	("creat", 8),
+	("create_module", 127),
+	("delete_module", 129),
+	("dup", 41),
+	("dup2", 63),
+	("dup3", 330),
+	("epoll_create", 254),
+	("epoll_create1", 329),
+	("epoll_ctl", 255),
+	("epoll_pwait", 319),
+	("epoll_pwait2", 441),
+	("epoll_wait", 256),
+	("eventfd", 323),
+	("eventfd2", 328),
+	("execve", 11),
+	("execveat", 358),
+	("exit", 1),
+	("exit_group", 252),
+	("faccessat", 307),
+	("faccessat2", 439),
+	("fadvise64", 250),
+	("fadvise64_64", 272),
+	("fallocate", 324),
+	("fanotify_init", 338),
+	("fanotify_mark", 339),
+	("fchdir", 133),
+	("fchmod", 94),
+	("fchmodat", 306),
+	("fchmodat2", 452),
+	("fchown", 95),
+	("fchown32", 207),
+	("fchownat", 298),
+	("fcntl", 55),
+	("fcntl64", 221),
+	("fdatasync", 148),
+	("fgetxattr", 231),
+	("finit_module", 350),
+	("flistxattr", 234),
+	("flock", 143),
+	("fork", 2),
+	("fremovexattr", 237),
+	("fsconfig", 431),
+	("fsetxattr", 228),
+	("fsmount", 432),
+	("fsopen", 430),
+	("fspick", 433),
+	("fstat", 108),
+	("fstat64", 197),
+	("fstatat64", 300),
+	("fstatfs", 100),
+	("fstatfs64", 269),
+	("fsync", 118),
+	("ftime", 35),
+	("ftruncate", 93),
+	("ftruncate64", 194),
+	("futex", 240),
+	("futex_requeue", 456),
+	("futex_time64", 422),
+	("futex_wait", 455),
+	("futex_waitv", 449),
+	("futex_wake", 454),
+	("futimesat", 299),
+	("get_kernel_syms", 130),
+	("get_mempolicy", 275),
+	("get_robust_list", 312),
+	("get_thread_area", 244),
+	("getcpu", 318),
+	("getcwd", 183),
+	("getdents", 141),
+	("getdents64", 220),
+	("getegid", 50),
+	("getegid32", 202),
+	("geteuid", 49),
+	("geteuid32", 201),
+	("getgid", 47),
+	("getgid32", 200),
+	("getgroups", 80),
+	("getgroups32", 205),
+	("getitimer", 105),
+	("getpeername", 368),
+	("getpgid", 132),
+	("getpgrp", 65),
+	("getpid", 20),
+	("getpmsg", 188),
+	("getppid", 64),
+	("getpriority", 96),
+	("getrandom", 355),
+	("getresgid", 171),
+	("getresgid32", 211),
+	("getresuid", 165),
+	("getresuid32", 209),
+	("getrlimit", 76),
+	("getrusage", 77),
+	("getsid", 147),
+	("getsockname", 367),
+	("getsockopt", 365),
+	("gettid", 224),
+	("gettimeofday", 78),
+	("getuid", 24),
+	("getuid32", 199),
+	("getxattr", 229),
+	("gtty", 32),
+	("idle", 112),
+	("init_module", 128),
+	("inotify_add_watch", 292),
+	("inotify_init", 291),
+	("inotify_init1", 332),
+	("inotify_rm_watch", 293),
+	("io_cancel", 249),
+	("io_destroy", 246),
+	("io_getevents", 247),
+	("io_pgetevents", 385),
+	("io_pgetevents_time64", 416),
+	("io_setup", 245),
+	("io_submit", 248),
+	("io_uring_enter", 426),
+	("io_uring_register", 427),
+	("io_uring_setup", 425),
+	("ioctl", 54),
+	("ioperm", 101),
+	("iopl", 110),
+	("ioprio_get", 290),
+	("ioprio_set", 289),
+	("ipc", 117),
+	("kcmp", 349),
+	("kexec_load", 283),
+	("keyctl", 288),
+	("kill", 37),
+	("landlock_add_rule", 445),
+	("landlock_create_ruleset", 444),
+	("landlock_restrict_self", 446),
+	("lchown", 16),
+	("lchown32", 198),
+	("lgetxattr", 230),
+	("link", 9),
+	("linkat", 303),
+	("listen", 363),
+	("listmount", 458),
+	("listxattr", 232),
+	("llistxattr", 233),
+	("lock", 53),
+	("lookup_dcookie", 253),
+	("lremovexattr", 236),
+	("lseek", 19),
+	("lsetxattr", 227),
+	("lsm_get_self_attr", 459),
+	("lsm_list_modules", 461),
+	("lsm_set_self_attr", 460),
+	("lstat", 107),
+	("lstat64", 196),
+	("madvise", 219),
+	("mbind", 274),
+	("membarrier", 375),
+	("memfd_create", 356),
+	("memfd_secret", 447),
+	("migrate_pages", 294),
+	("mincore", 218),
+	("mkdir", 39),
+	("mkdirat", 296),
+	("mknod", 14),
+	("mknodat", 297),
+	("mlock", 150),
+	("mlock2", 376),
+	("mlockall", 152),
+	("mmap", 90),
+	("mmap2", 192),
+	("modify_ldt", 123),
+	("mount", 21),
+	("mount_setattr", 442),
+	("move_mount", 429),
+	("move_pages", 317),
+	("mprotect", 125),
+	("mpx", 56),
+	("mq_getsetattr", 282),
+	("mq_notify", 281),
+	("mq_open", 277),
+	("mq_timedreceive", 280),
+	("mq_timedreceive_time64", 419),
+	("mq_timedsend", 279),
+	("mq_timedsend_time64", 418),
+	("mq_unlink", 278),
+	("mremap", 163),
+	("mseal", 462),
+	("msgctl", 402),
+	("msgget", 399),
+	("msgrcv", 401),
+	("msgsnd", 400),
+	("msync", 144),
+	("munlock", 151),
+	("munlockall", 153),
+	("munmap", 91),
+	("name_to_handle_at", 341),
+	("nanosleep", 162),
+	("nfsservctl", 169),
+	("nice", 34),
+	("oldfstat", 28),
+	("oldlstat", 84),
+	("oldolduname", 59),
+	("oldstat", 18),
+	("olduname", 109),
+	("open", 5),
+	("open_by_handle_at", 342),
+	("open_tree", 428),
+	("openat", 295),
+	("openat2", 437),
+	("pause", 29),
+	("perf_event_open", 336),
+	("personality", 136),
+	("pidfd_getfd", 438),
+	("pidfd_open", 434),
+	("pidfd_send_signal", 424),
+	("pipe", 42),
+	("pipe2", 331),
+	("pivot_root", 217),
+	("pkey_alloc", 381),
+	("pkey_free", 382),
+	("pkey_mprotect", 380),
+	("poll", 168),
+	("ppoll", 309),
+	("ppoll_time64", 414),
+	("prctl", 172),
+	("pread64", 180),
+	("preadv", 333),
+	("preadv2", 378),
+	("prlimit64", 340),
+	("process_madvise", 440),
+	("process_mrelease", 448),
+	("process_vm_readv", 347),
+	("process_vm_writev", 348),
+	("prof", 44),
+	("profil", 98),
+	("pselect6", 308),
+	("pselect6_time64", 413),
+	("ptrace", 26),
+	("putpmsg", 189),
+	("pwrite64", 181),
+	("pwritev", 334),
+	("pwritev2", 379),
+	("query_module", 167),
+	("quotactl", 131),
+	("quotactl_fd", 443),
+	("read", 3),
+	("readahead", 225),
+	("readdir", 89),
+	("readlink", 85),
+	("readlinkat", 305),
+	("readv", 145),
+	("reboot", 88),
+	("recvfrom", 371),
+	("recvmmsg", 337),
+	("recvmmsg_time64", 417),
+	("recvmsg", 372),
+	("remap_file_pages", 257),
+	("removexattr", 235),
+	("rename", 38),
+	("renameat", 302),
+	("renameat2", 353),
+	("request_key", 287),
+	("restart_syscall", 0),
+	("rmdir", 40),
+	("rseq", 386),
+	("rt_sigaction", 174),
+	("rt_sigpending", 176),
+	("rt_sigprocmask", 175),
+	("rt_sigqueueinfo", 178),
+	("rt_sigreturn", 173),
+	("rt_sigsuspend", 179),
+	("rt_sigtimedwait", 177),
+	("rt_sigtimedwait_time64", 421),
+	("rt_tgsigqueueinfo", 335),
+	("sched_get_priority_max", 159),
+	("sched_get_priority_min", 160),
+	("sched_getaffinity", 242),
+	("sched_getattr", 352),
+	("sched_getparam", 155),
+	("sched_getscheduler", 157),
+	("sched_rr_get_interval", 161),
+	("sched_rr_get_interval_time64", 423),
+	("sched_setaffinity", 241),
+	("sched_setattr", 351),
+	("sched_setparam", 154),
+	("sched_setscheduler", 156),
+	("sched_yield", 158),
+	("seccomp", 354),
+	("select", 82),
+	("semctl", 394),
+	("semget", 393),
+	("semtimedop_time64", 420),
+	("sendfile", 187),
+	("sendfile64", 239),
+	("sendmmsg", 345),
+	("sendmsg", 370),
+	("sendto", 369),
+	("set_mempolicy", 276),
+	("set_mempolicy_home_node", 450),
+	("set_robust_list", 311),
+	("set_thread_area", 243),
+	("set_tid_address", 258),
+	("setdomainname", 121),
+	("setfsgid", 139),
+	("setfsgid32", 216),
+	("setfsuid", 138),
+	("setfsuid32", 215),
+	("setgid", 46),
+	("setgid32", 214),
+	("setgroups", 81),
+	("setgroups32", 206),
+	("sethostname", 74),
+	("setitimer", 104),
+	("setns", 346),
+	("setpgid", 57),
+	("setpriority", 97),
+	("setregid", 71),
+	("setregid32", 204),
+	("setresgid", 170),
+	("setresgid32", 210),
+	("setresuid", 164),
+	("setresuid32", 208),
+	("setreuid", 70),
+	("setreuid32", 203),
+	("setrlimit", 75),
+	("setsid", 66),
+	("setsockopt", 366),
+	("settimeofday", 79),
+	("setuid", 23),
+	("setuid32", 213),
+	("setxattr", 226),
+	("sgetmask", 68),
+	("shmat", 397),
+	("shmctl", 396),
+	("shmdt", 398),
+	("shmget", 395),
+	("shutdown", 373),
+	("sigaction", 67),
+	("sigaltstack", 186),
+	("signal", 48),
+	("signalfd", 321),
+	("signalfd4", 327),
+	("sigpending", 73),
+	("sigprocmask", 126),
+	("sigreturn", 119),
+	("sigsuspend", 72),
+	("socket", 359),
+	("socketcall", 102),
+	("socketpair", 360),
+	("splice", 313),
+	("ssetmask", 69),
+	("stat", 106),
+	("stat64", 195),
+	("statfs", 99),
+	("statfs64", 268),
+	("statmount", 457),
+	("statx", 383),
+	("stime", 25),
+	("stty", 31),
+	("swapoff", 115),
+	("swapon", 87),
+	("symlink", 83),
+	("symlinkat", 304),
+	("sync", 36),
+	("sync_file_range", 314),
+	("syncfs", 344),
+	("sysfs", 135),
+	("sysinfo", 116),
+	("syslog", 103),
+	("tee", 315),
+	("tgkill", 270),
+	("time", 13),
+	("timer_create", 259),
+	("timer_delete", 263),
+	("timer_getoverrun", 262),
+	("timer_gettime", 261),
+	("timer_gettime64", 408),
+	("timer_settime", 260),
+	("timer_settime64", 409),
+	("timerfd_create", 322),
+	("timerfd_gettime", 326),
+	("timerfd_gettime64", 410),
+	("timerfd_settime", 325),
+	("timerfd_settime64", 411),
+	("times", 43),
+	("tkill", 238),
+	("truncate", 92),
+	("truncate64", 193),
+	("ugetrlimit", 191),
+	("ulimit", 58),
+	("umask", 60),
+	("umount", 22),
+	("umount2", 52),
+	("uname", 122),
+	("unlink", 10),
+	("unlinkat", 301),
+	("unshare", 310),
+	("uselib", 86),
+	("userfaultfd", 374),
+	("ustat", 62),
+	("utime", 30),
+	("utimensat", 320),
+	("utimensat_time64", 412),
+	("utimes", 271),
+	("vfork", 190),
+	("vhangup", 111),
+	("vm86", 166),
+	("vm86old", 113),
+	("vmsplice", 316),
+	("vserver", 273),
+	("wait4", 114),
+	("waitid", 284),
+	("waitpid", 7),
+	("write", 4),
+	("writev", 146),
+];
+
+/// System calls of x32 applications, which are able to run on x86_64 hosts
+#[cfg(target_arch = "x86_64")]
+pub const SYSCALLS_X32: SyscallTable = &[
+	("accept", X32_SYSCALL_BIT | 43),
+	("accept4", X32_SYSCALL_BIT | 288),
+	("access", X32_SYSCALL_BIT | 21),
+	("acct", X32_SYSCALL_BIT | 163),
+	("add_key", X32_SYSCALL_BIT | 248),
+	("adjtimex", X32_SYSCALL_BIT | 159),
+	("afs_syscall", X32_SYSCALL_BIT | 183),
+	("alarm", X32_SYSCALL_BIT | 37),
+	("arch_prctl", X32_SYSCALL_BIT | 158),
+	("bind", X32_SYSCALL_BIT | 49),
+	("bpf", X32_SYSCALL_BIT | 321),
+	("brk", X32_SYSCALL_BIT | 12),
+	("cachestat", X32_SYSCALL_BIT | 451),
+	("capget", X32_SYSCALL_BIT | 125),
+	("capset", X32_SYSCALL_BIT | 126),
+	("chdir", X32_SYSCALL_BIT | 80),
+	("chmod", X32_SYSCALL_BIT | 90),
+	("chown", X32_SYSCALL_BIT | 92),
+	("chroot", X32_SYSCALL_BIT | 161),
+	("clock_adjtime", X32_SYSCALL_BIT | 305),
+	("clock_getres", X32_SYSCALL_BIT | 229),
+	("clock_gettime", X32_SYSCALL_BIT | 228),
+	("clock_nanosleep", X32_SYSCALL_BIT | 230),
+	("clock_settime", X32_SYSCALL_BIT | 227),
+	("clone", X32_SYSCALL_BIT | 56),
+	("clone3", X32_SYSCALL_BIT | 435),
+	("close", X32_SYSCALL_BIT | 3),
+	("close_range", X32_SYSCALL_BIT | 436),
+	("connect", X32_SYSCALL_BIT | 42),
+	("copy_file_range", X32_SYSCALL_BIT | 326),
+	("creat", X32_SYSCALL_BIT | 85),
+	("delete_module", X32_SYSCALL_BIT | 176),
+	("dup", X32_SYSCALL_BIT | 32),
+	("dup2", X32_SYSCALL_BIT | 33),
+	("dup3", X32_SYSCALL_BIT | 292),
+	("epoll_create", X32_SYSCALL_BIT | 213),
+	("epoll_create1", X32_SYSCALL_BIT | 291),
+	("epoll_ctl", X32_SYSCALL_BIT | 233),
+	("epoll_pwait", X32_SYSCALL_BIT | 281),
+	("epoll_pwait2", X32_SYSCALL_BIT | 441),
+	("epoll_wait", X32_SYSCALL_BIT | 232),
+	("eventfd", X32_SYSCALL_BIT | 284),
+	("eventfd2", X32_SYSCALL_BIT | 290),
+	("execve", X32_SYSCALL_BIT | 520),
+	("execveat", X32_SYSCALL_BIT | 545),
+	("exit", X32_SYSCALL_BIT | 60),
+	("exit_group", X32_SYSCALL_BIT | 231),
+	("faccessat", X32_SYSCALL_BIT | 269),
+	("faccessat2", X32_SYSCALL_BIT | 439),
+	("fadvise64", X32_SYSCALL_BIT | 221),
+	("fallocate", X32_SYSCALL_BIT | 285),
+	("fanotify_init", X32_SYSCALL_BIT | 300),
+	("fanotify_mark", X32_SYSCALL_BIT | 301),
+	("fchdir", X32_SYSCALL_BIT | 81),
+	("fchmod", X32_SYSCALL_BIT | 91),
+	("fchmodat", X32_SYSCALL_BIT | 268),
+	("fchmodat2", X32_SYSCALL_BIT | 452),
+	("fchown", X32_SYSCALL_BIT | 93),
+	("fchownat", X32_SYSCALL_BIT | 260),
+	("fcntl", X32_SYSCALL_BIT | 72),
+	("fdatasync", X32_SYSCALL_BIT | 75),
+	("fgetxattr", X32_SYSCALL_BIT | 193),
+	("finit_module", X32_SYSCALL_BIT | 313),
+	("flistxattr", X32_SYSCALL_BIT | 196),
+	("flock", X32_SYSCALL_BIT | 73),
+	("fork", X32_SYSCALL_BIT | 57),
+	("fremovexattr", X32_SYSCALL_BIT | 199),
+	("fsconfig", X32_SYSCALL_BIT | 431),
+	("fsetxattr", X32_SYSCALL_BIT | 190),
+	("fsmount", X32_SYSCALL_BIT | 432),
+	("fsopen", X32_SYSCALL_BIT | 430),
+	("fspick", X32_SYSCALL_BIT | 433),
+	("fstat", X32_SYSCALL_BIT | 5),
+	("fstatfs", X32_SYSCALL_BIT | 138),
+	("fsync", X32_SYSCALL_BIT | 74),
+	("ftruncate", X32_SYSCALL_BIT | 77),
+	("futex", X32_SYSCALL_BIT | 202),
+	("futex_requeue", X32_SYSCALL_BIT | 456),
+	("futex_wait", X32_SYSCALL_BIT | 455),
+	("futex_waitv", X32_SYSCALL_BIT | 449),
+	("futex_wake", X32_SYSCALL_BIT | 454),
+	("futimesat", X32_SYSCALL_BIT | 261),
+	("get_mempolicy", X32_SYSCALL_BIT | 239),
+	("get_robust_list", X32_SYSCALL_BIT | 531),
+	("getcpu", X32_SYSCALL_BIT | 309),
+	("getcwd", X32_SYSCALL_BIT | 79),
+	("getdents", X32_SYSCALL_BIT | 78),
+	("getdents64", X32_SYSCALL_BIT | 217),
+	("getegid", X32_SYSCALL_BIT | 108),
+	("geteuid", X32_SYSCALL_BIT | 107),
+	("getgid", X32_SYSCALL_BIT | 104),
+	("getgroups", X32_SYSCALL_BIT | 115),
+	("getitimer", X32_SYSCALL_BIT | 36),
+	("getpeername", X32_SYSCALL_BIT | 52),
+	("getpgid", X32_SYSCALL_BIT | 121),
+	("getpgrp", X32_SYSCALL_BIT | 111),
+	("getpid", X32_SYSCALL_BIT | 39),
+	("getpmsg", X32_SYSCALL_BIT | 181),
+	("getppid", X32_SYSCALL_BIT | 110),
+	("getpriority", X32_SYSCALL_BIT | 140),
+	("getrandom", X32_SYSCALL_BIT | 318),
+	("getresgid", X32_SYSCALL_BIT | 120),
+	("getresuid", X32_SYSCALL_BIT | 118),
+	("getrlimit", X32_SYSCALL_BIT | 97),
+	("getrusage", X32_SYSCALL_BIT | 98),
+	("getsid", X32_SYSCALL_BIT | 124),
+	("getsockname", X32_SYSCALL_BIT | 51),
+	("getsockopt", X32_SYSCALL_BIT | 542),
+	("gettid", X32_SYSCALL_BIT | 186),
+	("gettimeofday", X32_SYSCALL_BIT | 96),
+	("getuid", X32_SYSCALL_BIT | 102),
+	("getxattr", X32_SYSCALL_BIT | 191),
+	("init_module", X32_SYSCALL_BIT | 175),
+	("inotify_add_watch", X32_SYSCALL_BIT | 254),
+	("inotify_init", X32_SYSCALL_BIT | 253),
+	("inotify_init1", X32_SYSCALL_BIT | 294),
+	("inotify_rm_watch", X32_SYSCALL_BIT | 255),
+	("io_cancel", X32_SYSCALL_BIT | 210),
+	("io_destroy", X32_SYSCALL_BIT | 207),
+	("io_getevents", X32_SYSCALL_BIT | 208),
+	("io_pgetevents", X32_SYSCALL_BIT | 333),
+	("io_setup", X32_SYSCALL_BIT | 543),
+	("io_submit", X32_SYSCALL_BIT | 544),
+	("io_uring_enter", X32_SYSCALL_BIT | 426),
+	("io_uring_register", X32_SYSCALL_BIT | 427),
+	("io_uring_setup", X32_SYSCALL_BIT | 425),
+	("ioctl", X32_SYSCALL_BIT | 514),
+	("ioperm", X32_SYSCALL_BIT | 173),
+	("iopl", X32_SYSCALL_BIT | 172),
+	("ioprio_get", X32_SYSCALL_BIT | 252),
+	("ioprio_set", X32_SYSCALL_BIT | 251),
+	("kcmp", X32_SYSCALL_BIT | 312),
+	("kexec_file_load", X32_SYSCALL_BIT | 320),
+	("kexec_load", X32_SYSCALL_BIT | 528),
+	("keyctl", X32_SYSCALL_BIT | 250),
+	("kill", X32_SYSCALL_BIT | 62),
+	("landlock_add_rule", X32_SYSCALL_BIT | 445),
+	("landlock_create_ruleset", X32_SYSCALL_BIT | 444),
+	("landlock_restrict_self", X32_SYSCALL_BIT | 446),
+	("lchown", X32_SYSCALL_BIT | 94),
+	("lgetxattr", X32_SYSCALL_BIT | 192),
+	("link", X32_SYSCALL_BIT | 86),
+	("linkat", X32_SYSCALL_BIT | 265),
+	("listen", X32_SYSCALL_BIT | 50),
+	("listmount", X32_SYSCALL_BIT | 458),
+	("listxattr", X32_SYSCALL_BIT | 194),
+	("llistxattr", X32_SYSCALL_BIT | 195),
+	("lookup_dcookie", X32_SYSCALL_BIT | 212),
+	("lremovexattr", X32_SYSCALL_BIT | 198),
+	("lseek", X32_SYSCALL_BIT | 8),
+	("lsetxattr", X32_SYSCALL_BIT | 189),
+	("lsm_get_self_attr", X32_SYSCALL_BIT | 459),
+	("lsm_list_modules", X32_SYSCALL_BIT | 461),
+	("lsm_set_self_attr", X32_SYSCALL_BIT | 460),
+	("lstat", X32_SYSCALL_BIT | 6),
+	("madvise", X32_SYSCALL_BIT | 28),
+	("mbind", X32_SYSCALL_BIT | 237),
+	("membarrier", X32_SYSCALL_BIT | 324),
+	("memfd_create", X32_SYSCALL_BIT | 319),
+	("memfd_secret", X32_SYSCALL_BIT | 447),
+	("migrate_pages", X32_SYSCALL_BIT | 256),
+	("mincore", X32_SYSCALL_BIT | 27),
+	("mkdir", X32_SYSCALL_BIT | 83),
+	("mkdirat", X32_SYSCALL_BIT | 258),
+	("mknod", X32_SYSCALL_BIT | 133),
+	("mknodat", X32_SYSCALL_BIT | 259),
+	("mlock", X32_SYSCALL_BIT | 149),
+	("mlock2", X32_SYSCALL_BIT | 325),
+	("mlockall", X32_SYSCALL_BIT | 151),
+	("mmap", X32_SYSCALL_BIT | 9),
+	("modify_ldt", X32_SYSCALL_BIT | 154),
+	("mount", X32_SYSCALL_BIT | 165),
+	("mount_setattr", X32_SYSCALL_BIT | 442),
+	("move_mount", X32_SYSCALL_BIT | 429),
+	("move_pages", X32_SYSCALL_BIT | 533),
+	("mprotect", X32_SYSCALL_BIT | 10),
+	("mq_getsetattr", X32_SYSCALL_BIT | 245),
+	("mq_notify", X32_SYSCALL_BIT | 527),
+	("mq_open", X32_SYSCALL_BIT | 240),
+	("mq_timedreceive", X32_SYSCALL_BIT | 243),
+	("mq_timedsend", X32_SYSCALL_BIT | 242),
+	("mq_unlink", X32_SYSCALL_BIT | 241),
+	("mremap", X32_SYSCALL_BIT | 25),
+	("mseal", X32_SYSCALL_BIT | 462),
+	("msgctl", X32_SYSCALL_BIT | 71),
+	("msgget", X32_SYSCALL_BIT | 68),
+	("msgrcv", X32_SYSCALL_BIT | 70),
+	("msgsnd", X32_SYSCALL_BIT | 69),
+	("msync", X32_SYSCALL_BIT | 26),
+	("munlock", X32_SYSCALL_BIT | 150),
+	("munlockall", X32_SYSCALL_BIT | 152),
+	("munmap", X32_SYSCALL_BIT | 11),
+	("name_to_handle_at", X32_SYSCALL_BIT | 303),
+	("nanosleep", X32_SYSCALL_BIT | 35),
+	("newfstatat", X32_SYSCALL_BIT | 262),
+	("open", X32_SYSCALL_BIT | 2),
+	("open_by_handle_at", X32_SYSCALL_BIT | 304),
+	("open_tree", X32_SYSCALL_BIT | 428),
+	("openat", X32_SYSCALL_BIT | 257),
+	("openat2", X32_SYSCALL_BIT | 437),
+	("pause", X32_SYSCALL_BIT | 34),
+	("perf_event_open", X32_SYSCALL_BIT | 298),
+	("personality", X32_SYSCALL_BIT | 135),
+	("pidfd_getfd", X32_SYSCALL_BIT | 438),
+	("pidfd_open", X32_SYSCALL_BIT | 434),
+	("pidfd_send_signal", X32_SYSCALL_BIT | 424),
+	("pipe", X32_SYSCALL_BIT | 22),
+	("pipe2", X32_SYSCALL_BIT | 293),
+	("pivot_root", X32_SYSCALL_BIT | 155),
+	("pkey_alloc", X32_SYSCALL_BIT | 330),
+	("pkey_free", X32_SYSCALL_BIT | 331),
+	("pkey_mprotect", X32_SYSCALL_BIT | 329),
+	("poll", X32_SYSCALL_BIT | 7),
+	("ppoll", X32_SYSCALL_BIT | 271),
+	("prctl", X32_SYSCALL_BIT | 157),
+	("pread64", X32_SYSCALL_BIT | 17),
+	("preadv", X32_SYSCALL_BIT | 534),
+	("preadv2", X32_SYSCALL_BIT | 546),
+	("prlimit64", X32_SYSCALL_BIT | 302),
+	("process_madvise", X32_SYSCALL_BIT | 440),
+	("process_mrelease", X32_SYSCALL_BIT | 448),
+	("process_vm_readv", X32_SYSCALL_BIT | 539),
+	("process_vm_writev", X32_SYSCALL_BIT | 540),
+	("pselect6", X32_SYSCALL_BIT | 270),
+	("ptrace", X32_SYSCALL_BIT | 521),
+	("putpmsg", X32_SYSCALL_BIT | 182),
+	("pwrite64", X32_SYSCALL_BIT | 18),
+	("pwritev", X32_SYSCALL_BIT | 535),
+	("pwritev2", X32_SYSCALL_BIT | 547),
+	("quotactl", X32_SYSCALL_BIT | 179),
+	("quotactl_fd", X32_SYSCALL_BIT | 443),
+	("read", X32_SYSCALL_BIT),
+	("readahead", X32_SYSCALL_BIT | 187),
+	("readlink", X32_SYSCALL_BIT | 89),
+	("readlinkat", X32_SYSCALL_BIT | 267),
+	("readv", X32_SYSCALL_BIT | 515),
+	("reboot", X32_SYSCALL_BIT | 169),
+	("recvfrom", X32_SYSCALL_BIT | 517),
+	("recvmmsg", X32_SYSCALL_BIT | 537),
+	("recvmsg", X32_SYSCALL_BIT | 519),
+	("remap_file_pages", X32_SYSCALL_BIT | 216),
+	("removexattr", X32_SYSCALL_BIT | 197),
+	("rename", X32_SYSCALL_BIT | 82),
+	("renameat", X32_SYSCALL_BIT | 264),
+	("renameat2", X32_SYSCALL_BIT | 316),
+	("request_key", X32_SYSCALL_BIT | 249),
+	("restart_syscall", X32_SYSCALL_BIT | 219),
+	("rmdir", X32_SYSCALL_BIT | 84),
+	("rseq", X32_SYSCALL_BIT | 334),
+	("rt_sigaction", X32_SYSCALL_BIT | 512),
+	("rt_sigpending", X32_SYSCALL_BIT | 522),
+	("rt_sigprocmask", X32_SYSCALL_BIT | 14),
+	("rt_sigqueueinfo", X32_SYSCALL_BIT | 524),
+	("rt_sigreturn", X32_SYSCALL_BIT | 513),
+	("rt_sigsuspend", X32_SYSCALL_BIT | 130),
+	("rt_sigtimedwait", X32_SYSCALL_BIT | 523),
+	("rt_tgsigqueueinfo", X32_SYSCALL_BIT | 536),
+	("sched_get_priority_max", X32_SYSCALL_BIT | 146),
+	("sched_get_priority_min", X32_SYSCALL_BIT | 147),
+	("sched_getaffinity", X32_SYSCALL_BIT | 204),
+	("sched_getattr", X32_SYSCALL_BIT | 315),
+	("sched_getparam", X32_SYSCALL_BIT | 143),
+	("sched_getscheduler", X32_SYSCALL_BIT | 145),
+	("sched_rr_get_interval", X32_SYSCALL_BIT | 148),
+	("sched_setaffinity", X32_SYSCALL_BIT | 203),
+	("sched_setattr", X32_SYSCALL_BIT | 314),
+	("sched_setparam", X32_SYSCALL_BIT | 142),
+	("sched_setscheduler", X32_SYSCALL_BIT | 144),
+	("sched_yield", X32_SYSCALL_BIT | 24),
+	("seccomp", X32_SYSCALL_BIT | 317),
+	("security", X32_SYSCALL_BIT | 185),
+	("select", X32_SYSCALL_BIT | 23),
+	("semctl", X32_SYSCALL_BIT | 66),
+	("semget", X32_SYSCALL_BIT | 64),
+	("semop", X32_SYSCALL_BIT | 65),
+	("semtimedop", X32_SYSCALL_BIT | 220),
+	("sendfile", X32_SYSCALL_BIT | 40),
+	("sendmmsg", X32_SYSCALL_BIT | 538),
+	("sendmsg", X32_SYSCALL_BIT | 518),
+	("sendto", X32_SYSCALL_BIT | 44),
+	("set_mempolicy", X32_SYSCALL_BIT | 238),
+	("set_mempolicy_home_node", X32_SYSCALL_BIT | 450),
+	("set_robust_list", X32_SYSCALL_BIT | 530),
+	("set_tid_address", X32_SYSCALL_BIT | 218),
+	("setdomainname", X32_SYSCALL_BIT | 171),
+	("setfsgid", X32_SYSCALL_BIT | 123),
+	("setfsuid", X32_SYSCALL_BIT | 122),
+	("setgid", X32_SYSCALL_BIT | 106),
+	("setgroups", X32_SYSCALL_BIT | 116),
+	("sethostname", X32_SYSCALL_BIT | 170),
+	("setitimer", X32_SYSCALL_BIT | 38),
+	("setns", X32_SYSCALL_BIT | 308),
+	("setpgid", X32_SYSCALL_BIT | 109),
+	("setpriority", X32_SYSCALL_BIT | 141),
+	("setregid", X32_SYSCALL_BIT | 114),
+	("setresgid", X32_SYSCALL_BIT | 119),
+	("setresuid", X32_SYSCALL_BIT | 117),
+	("setreuid", X32_SYSCALL_BIT | 113),
+	("setrlimit", X32_SYSCALL_BIT | 160),
+	("setsid", X32_SYSCALL_BIT | 112),
+	("setsockopt", X32_SYSCALL_BIT | 541),
+	("settimeofday", X32_SYSCALL_BIT | 164),
+	("setuid", X32_SYSCALL_BIT | 105),
+	("setxattr", X32_SYSCALL_BIT | 188),
+	("shmat", X32_SYSCALL_BIT | 30),
+	("shmctl", X32_SYSCALL_BIT | 31),
+	("shmdt", X32_SYSCALL_BIT | 67),
+	("shmget", X32_SYSCALL_BIT | 29),
+	("shutdown", X32_SYSCALL_BIT | 48),
+	("sigaltstack", X32_SYSCALL_BIT | 525),
+	("signalfd", X32_SYSCALL_BIT | 282),
+	("signalfd4", X32_SYSCALL_BIT | 289),
+	("socket", X32_SYSCALL_BIT | 41),
+	("socketpair", X32_SYSCALL_BIT | 53),
+	("splice", X32_SYSCALL_BIT | 275),
+	("stat", X32_SYSCALL_BIT | 4),
+	("statfs", X32_SYSCALL_BIT | 137),
+	("statmount", X32_SYSCALL_BIT | 457),
+	("statx", X32_SYSCALL_BIT | 332),
+	("swapoff", X32_SYSCALL_BIT | 168),
+	("swapon", X32_SYSCALL_BIT | 167),
+	("symlink", X32_SYSCALL_BIT | 88),
+	("symlinkat", X32_SYSCALL_BIT | 266),
+	("sync", X32_SYSCALL_BIT | 162),
+	("sync_file_range", X32_SYSCALL_BIT | 277),
+	("syncfs", X32_SYSCALL_BIT | 306),
+	("sysfs", X32_SYSCALL_BIT | 139),
+	("sysinfo", X32_SYSCALL_BIT | 99),
+	("syslog", X32_SYSCALL_BIT | 103),
+	("tee", X32_SYSCALL_BIT | 276),
+	("tgkill", X32_SYSCALL_BIT | 234),
+	("time", X32_SYSCALL_BIT | 201),
+	("timer_create", X32_SYSCALL_BIT | 526),
+	("timer_delete", X32_SYSCALL_BIT | 226),
+	("timer_getoverrun", X32_SYSCALL_BIT | 225),
+	("timer_gettime", X32_SYSCALL_BIT | 224),
+	("timer_settime", X32_SYSCALL_BIT | 223),
+	("timerfd_create", X32_SYSCALL_BIT | 283),
+	("timerfd_gettime", X32_SYSCALL_BIT | 287),
+	("timerfd_settime", X32_SYSCALL_BIT | 286),
+	("times", X32_SYSCALL_BIT | 100),
+	("tkill", X32_SYSCALL_BIT | 200),
+	("truncate", X32_SYSCALL_BIT | 76),
+	("tuxcall", X32_SYSCALL_BIT | 184),
+	("umask", X32_SYSCALL_BIT | 95),
+	("umount2", X32_SYSCALL_BIT | 166),
+	("uname", X32_SYSCALL_BIT | 63),
+	("unlink", X32_SYSCALL_BIT | 87),
+	("unlinkat", X32_SYSCALL_BIT | 263),
+	("unshare", X32_SYSCALL_BIT | 272),
+	("userfaultfd", X32_SYSCALL_BIT | 323),
+	("ustat", X32_SYSCALL_BIT | 136),
+	("utime", X32_SYSCALL_BIT | 132),
+	("utimensat", X32_SYSCALL_BIT | 280),
+	("utimes", X32_SYSCALL_BIT | 235),
+	("vfork", X32_SYSCALL_BIT | 58),
+	("vhangup", X32_SYSCALL_BIT | 153),
+	("vmsplice", X32_SYSCALL_BIT | 532),
+	("wait4", X32_SYSCALL_BIT | 61),
+	("waitid", X32_SYSCALL_BIT | 529),
+	("write", X32_SYSCALL_BIT | 1),
+	("writev", X32_SYSCALL_BIT | 516),
+];
+
+/// Architectures besides the native one, whose applications the kernel of the host is able to
+/// run, with their system calls. Architectures without a table cannot be filtered by runh.
+#[cfg(target_arch = "x86_64")]
+pub const COMPAT_SYSCALLS: &[(Arch, Option<SyscallTable>)] = &[
+	(Arch::ScmpArchX86, Some(SYSCALLS_X86)),
+	(Arch::ScmpArchX32, Some(SYSCALLS_X32)),
+];
+
+#[cfg(target_arch = "aarch64")]
+pub const COMPAT_SYSCALLS: &[(Arch, Option<SyscallTable>)] = &[(Arch::ScmpArchArm, None)];
+
+#[cfg(target_arch = "riscv64")]
+pub const COMPAT_SYSCALLS: &[(Arch, Option<SyscallTable>)] = &[];
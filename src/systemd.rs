@@ -0,0 +1,491 @@
+use crate::{cgroups, rootless};
+use oci_spec::runtime::{LinuxResources, Spec};
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use zbus::zvariant::Value;
+
+const SYSTEMD_DESTINATION: &str = "org.freedesktop.systemd1";
+const SYSTEMD_PATH: &str = "/org/freedesktop/systemd1";
+const SYSTEMD_MANAGER: &str = "org.freedesktop.systemd1.Manager";
+
+const SCOPE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connects to the systemd instance of the user for rootless containers and to the system
+/// instance otherwise.
+async fn connect() -> zbus::Result<zbus::Connection> {
+	if rootless::is_rootless() {
+		zbus::Connection::session().await
+	} else {
+		zbus::Connection::system().await
+	}
+}
+
+/// Returns the cgroup of the systemd instance, below which it places its units.
+fn get_manager_cgroup() -> PathBuf {
+	if !rootless::is_rootless() {
+		return PathBuf::from(cgroups::CGROUP_ROOT);
+	}
+
+	let tokio_runtime = tokio::runtime::Runtime::new().expect("Could not spawn new tokio runtime!");
+	let control_group: String = tokio_runtime
+		.block_on(async {
+			let connection = connect().await?;
+			let reply = connection
+				.call_method(
+					Some(SYSTEMD_DESTINATION),
+					SYSTEMD_PATH,
+					Some("org.freedesktop.DBus.Properties"),
+					"Get",
+					&(SYSTEMD_MANAGER, "ControlGroup"),
+				)
+				.await?;
+			let value: zbus::zvariant::OwnedValue = reply.body().deserialize()?;
+			Ok::<String, zbus::Error>(String::try_from(value)?)
+		})
+		.unwrap_or_else(|err| {
+			panic!(
+				"Could not query cgroup of the systemd user instance: {}",
+				err
+			)
+		});
+	Path::new(cgroups::CGROUP_ROOT).join(control_group.trim_start_matches('/'))
+}
+
+/// Splits a `linux.cgroupsPath` in the `slice:prefix:name` format of the systemd cgroup driver
+/// into the slice and the name of the scope unit.
+fn parse_cgroups_path(spec: &Spec, id: &str) -> (String, String) {
+	let cgroups_path = spec
+		.linux()
+		.as_ref()
+		.and_then(|linux| linux.cgroups_path().as_ref())
+		.map(|path| path.to_str().unwrap().to_owned())
+		.unwrap_or_else(|| format!(":runh:{id}"));
+
+	let parts: Vec<&str> = cgroups_path.split(':').collect();
+	let [slice, prefix, name] = parts[..] else {
+		panic!(
+			"cgroupsPath {} does not match the format slice:prefix:name expected by the systemd cgroup driver!",
+			cgroups_path
+		);
+	};
+
+	if name.ends_with(".slice") {
+		panic!(
+			"Placing containers directly into the slice {} is not supported by runh!",
+			name
+		);
+	}
+
+	let slice = if slice.is_empty() && rootless::is_rootless() {
+		"user.slice".to_string()
+	} else if slice.is_empty() {
+		"system.slice".to_string()
+	} else {
+		slice.to_string()
+	};
+	let unit = if prefix.is_empty() {
+		format!("{name}.scope")
+	} else {
+		format!("{prefix}-{name}.scope")
+	};
+
+	(slice, unit)
+}
+
+/// Expands a slice name into its cgroup path, e.g. `a-b.slice` into `a.slice/a-b.slice`.
+fn expand_slice(slice: &str) -> PathBuf {
+	let name = slice
+		.strip_suffix(".slice")
+		.unwrap_or_else(|| panic!("{} is not a valid slice name!", slice));
+	if name.contains('/') {
+		panic!("{} is not a valid slice name!", slice);
+	}
+
+	let mut path = PathBuf::new();
+	// The root slice `-.slice` corresponds to the cgroup root
+	if name == "-" {
+		return path;
+	}
+
+	let mut prefix = String::new();
+	for component in name.split('-') {
+		if component.is_empty() {
+			panic!("{} is not a valid slice name!", slice);
+		}
+		prefix.push_str(component);
+		path.push(format!("{prefix}.slice"));
+		prefix.push('-');
+	}
+	path
+}
+
+/// Determines the cgroup, which systemd creates for the scope unit of the container.
+pub fn get_cgroup_path(spec: &Spec, id: &str) -> PathBuf {
+	let (slice, unit) = parse_cgroups_path(spec, id);
+	get_manager_cgroup().join(expand_slice(&slice)).join(unit)
+}
+
+/// Converts a cpuset list like `0-3,7` into the bitmask expected by systemd.
+fn get_cpuset_bitmask(cpuset: &str) -> Vec<u8> {
+	let mut bitmask: Vec<u8> = Vec::new();
+	for bit in cgroups::parse_cpuset(cpuset) {
+		if bitmask.len() <= bit / 8 {
+			bitmask.resize(bit / 8 + 1, 0);
+		}
+		bitmask[bit / 8] |= 1 << (bit % 8);
+	}
+	bitmask
+}
+
+fn get_limit_property(limit: i64) -> u64 {
+	if limit < 0 {
+		u64::MAX
+	} else {
+		limit as u64
+	}
+}
+
+/// Translates the resource limits into properties of the scope unit. This prevents systemd from
+/// resetting the limits, which are additionally written to the cgroup files, on a reload.
+fn get_resource_properties(resources: &LinuxResources) -> Vec<(&'static str, Value<'static>)> {
+	let mut properties: Vec<(&'static str, Value<'static>)> = Vec::new();
+
+	if let Some(memory) = resources.memory() {
+		if let Some(limit) = memory.limit() {
+			properties.push(("MemoryMax", Value::from(get_limit_property(limit))));
+		}
+		if let Some(reservation) = memory.reservation() {
+			properties.push(("MemoryLow", Value::from(get_limit_property(reservation))));
+		}
+		if let Some(swap) = memory.swap() {
+			let swap_max = match memory.limit() {
+				Some(limit) if swap >= 0 && limit > 0 => swap - limit,
+				_ => swap,
+			};
+			properties.push(("MemorySwapMax", Value::from(get_limit_property(swap_max))));
+		}
+	}
+
+	if let Some(cpu) = resources.cpu() {
+		if let Some(shares) = cpu.shares() {
+			let weight = 1 + ((shares.clamp(2, 262144) - 2) * 9999) / 262142;
+			properties.push(("CPUWeight", Value::from(weight)));
+		}
+		if let Some(quota) = cpu.quota().filter(|quota| *quota > 0) {
+			let period = cpu.period().unwrap_or(100000);
+			// systemd only supports a granularity of 10ms per second, so round up
+			let quota_per_sec = (quota as u64 * 1000000).div_ceil(period).div_ceil(10000) * 10000;
+			properties.push(("CPUQuotaPerSecUSec", Value::from(quota_per_sec)));
+			properties.push(("CPUQuotaPeriodUSec", Value::from(period)));
+		}
+		if let Some(cpus) = cpu.cpus() {
+			properties.push(("AllowedCPUs", Value::from(get_cpuset_bitmask(cpus))));
+		}
+		if let Some(mems) = cpu.mems() {
+			properties.push(("AllowedMemoryNodes", Value::from(get_cpuset_bitmask(mems))));
+		}
+	}
+
+	if let Some(pids) = resources.pids() {
+		properties.push(("TasksMax", Value::from(get_limit_property(pids.limit()))));
+	}
+
+	if let Some(weight) = resources.block_io().as_ref().and_then(|x| x.weight()) {
+		let weight = 1 + ((weight.clamp(10, 1000) as u64 - 10) * 9999) / 990;
+		properties.push(("IOWeight", Value::from(weight)));
+	}
+
+	properties
+}
+
+/// Asks systemd to create a transient scope unit for the container containing the process `pid`.
+async fn start_transient_unit(
+	connection: &zbus::Connection,
+	spec: &Spec,
+	id: &str,
+	pid: u32,
+) -> zbus::Result<()> {
+	let (slice, unit) = parse_cgroups_path(spec, id);
+	let mut properties: Vec<(&str, Value)> = vec![
+		("Description", Value::from(format!("runh container {id}"))),
+		("Slice", Value::from(slice)),
+		("PIDs", Value::from(vec![pid])),
+		("Delegate", Value::from(true)),
+		("DefaultDependencies", Value::from(false)),
+		("MemoryAccounting", Value::from(true)),
+		("CPUAccounting", Value::from(true)),
+		("IOAccounting", Value::from(true)),
+		("TasksAccounting", Value::from(true)),
+	];
+	if let Some(resources) = spec
+		.linux()
+		.as_ref()
+		.and_then(|linux| linux.resources().as_ref())
+	{
+		properties.append(&mut get_resource_properties(resources));
+	}
+	let aux: Vec<(&str, Vec<(&str, Value)>)> = Vec::new();
+
+	debug!("Start transient unit {unit}");
+	connection
+		.call_method(
+			Some(SYSTEMD_DESTINATION),
+			SYSTEMD_PATH,
+			Some(SYSTEMD_MANAGER),
+			"StartTransientUnit",
+			&(unit.as_str(), "replace", properties, aux),
+		)
+		.await?;
+	Ok(())
+}
+
+/// Creates a transient scope unit for the container and places the process `pid` in it.
+pub fn start_scope(spec: &Spec, id: &str, pid: u32) {
+	let cgroup_path = get_cgroup_path(spec, id);
+
+	let tokio_runtime = tokio::runtime::Runtime::new().expect("Could not spawn new tokio runtime!");
+	tokio_runtime
+		.block_on(async {
+			let connection = connect().await?;
+			start_transient_unit(&connection, spec, id, pid).await
+		})
+		.unwrap_or_else(|err| {
+			panic!(
+				"Could not start transient unit for container {}: {}",
+				id, err
+			)
+		});
+
+	// The unit is started asynchronously by a systemd job
+	let start = Instant::now();
+	while cgroups::get_process_cgroup(pid as i32).as_ref() != Some(&cgroup_path) {
+		if start.elapsed() > SCOPE_TIMEOUT {
+			panic!(
+				"Timeout while waiting for systemd to move the container into {:?}!",
+				cgroup_path
+			);
+		}
+		std::thread::sleep(Duration::from_millis(10));
+	}
+}
+
+/// Stops the scope unit, which contains the cgroup at `cgroup_path`.
+pub fn stop_scope(cgroup_path: &Path) {
+	let unit = cgroup_path
+		.file_name()
+		.and_then(|name| name.to_str())
+		.expect("Could not determine the name of the scope unit!");
+
+	debug!("Stop transient unit {unit}");
+	let tokio_runtime = tokio::runtime::Runtime::new().expect("Could not spawn new tokio runtime!");
+	if let Err(err) = tokio_runtime.block_on(async {
+		let connection = connect().await?;
+		connection
+			.call_method(
+				Some(SYSTEMD_DESTINATION),
+				SYSTEMD_PATH,
+				Some(SYSTEMD_MANAGER),
+				"StopUnit",
+				&(unit, "replace"),
+			)
+			.await
+	}) {
+		// The scope is removed automatically by systemd as soon as it is empty
+		debug!("Could not stop transient unit {unit}: {err}");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::{BufRead, BufReader};
+	use std::process::{Child, Command, Stdio};
+	use std::sync::{Arc, Mutex};
+	use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+	type Properties = Vec<(String, OwnedValue)>;
+
+	/// Records the calls of StartTransientUnit instead of starting units
+	struct MockManager {
+		calls: Arc<Mutex<Vec<(String, String, Properties)>>>,
+	}
+
+	#[zbus::interface(name = "org.freedesktop.systemd1.Manager")]
+	impl MockManager {
+		fn start_transient_unit(
+			&self,
+			name: String,
+			mode: String,
+			properties: Properties,
+			_aux: Vec<(String, Properties)>,
+		) -> OwnedObjectPath {
+			self.calls.lock().unwrap().push((name, mode, properties));
+			OwnedObjectPath::try_from("/org/freedesktop/systemd1/job/1").unwrap()
+		}
+	}
+
+	/// A private message bus, which is stopped when dropped
+	struct PrivateBus {
+		daemon: Child,
+		address: String,
+		dir: PathBuf,
+	}
+
+	impl PrivateBus {
+		fn start() -> Option<Self> {
+			let Some(daemon_path) = crate::paths::find_in_path(Path::new("dbus-daemon"), None)
+			else {
+				eprintln!("dbus-daemon is not installed, skipping test");
+				return None;
+			};
+
+			let dir = std::env::temp_dir().join(format!("runh-test-bus-{}", std::process::id()));
+			std::fs::create_dir_all(&dir).unwrap();
+			let config = dir.join("bus.conf");
+			std::fs::write(
+				&config,
+				format!(
+					r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+<busconfig>
+	<type>session</type>
+	<listen>unix:path={}</listen>
+	<auth>EXTERNAL</auth>
+	<policy context="default">
+		<allow send_destination="*" eavesdrop="true"/>
+		<allow eavesdrop="true"/>
+		<allow own="*"/>
+	</policy>
+</busconfig>
+"#,
+					dir.join("bus").display()
+				),
+			)
+			.unwrap();
+
+			let mut daemon = Command::new(daemon_path)
+				.arg(format!("--config-file={}", config.display()))
+				.arg("--nofork")
+				.arg("--print-address")
+				.stdout(Stdio::piped())
+				.stderr(Stdio::null())
+				.spawn()
+				.unwrap();
+			let mut address = String::new();
+			BufReader::new(daemon.stdout.take().unwrap())
+				.read_line(&mut address)
+				.unwrap();
+			Some(Self {
+				daemon,
+				address: address.trim().to_string(),
+				dir,
+			})
+		}
+	}
+
+	impl Drop for PrivateBus {
+		fn drop(&mut self) {
+			let _ = self.daemon.kill();
+			let _ = self.daemon.wait();
+			let _ = std::fs::remove_dir_all(&self.dir);
+		}
+	}
+
+	fn get_property<'a>(properties: &'a Properties, name: &str) -> &'a OwnedValue {
+		&properties
+			.iter()
+			.find(|(key, _)| key == name)
+			.unwrap_or_else(|| panic!("Property {} is missing", name))
+			.1
+	}
+
+	#[test]
+	fn start_transient_unit_with_mock_manager() {
+		let Some(bus) = PrivateBus::start() else {
+			return;
+		};
+
+		let mut spec = Spec::default();
+		spec.set_linux(Some(
+			serde_json::from_value(serde_json::json!({
+				"cgroupsPath": "machine.slice:libpod:abc",
+				"resources": {
+					"memory": { "limit": 536870912 },
+					"cpu": { "shares": 1024, "cpus": "0-2,8" },
+					"pids": { "limit": 100 }
+				}
+			}))
+			.unwrap(),
+		));
+
+		let calls = Arc::new(Mutex::new(Vec::new()));
+		let tokio_runtime = tokio::runtime::Runtime::new().unwrap();
+		tokio_runtime.block_on(async {
+			let _manager = zbus::connection::Builder::address(bus.address.as_str())
+				.unwrap()
+				.name(SYSTEMD_DESTINATION)
+				.unwrap()
+				.serve_at(
+					SYSTEMD_PATH,
+					MockManager {
+						calls: calls.clone(),
+					},
+				)
+				.unwrap()
+				.build()
+				.await
+				.unwrap();
+			let connection = zbus::connection::Builder::address(bus.address.as_str())
+				.unwrap()
+				.build()
+				.await
+				.unwrap();
+			start_transient_unit(&connection, &spec, "abc", 1234)
+				.await
+				.unwrap();
+		});
+
+		let calls = calls.lock().unwrap();
+		assert_eq!(calls.len(), 1);
+		let (name, mode, properties) = &calls[0];
+		assert_eq!(name, "libpod-abc.scope");
+		assert_eq!(mode, "replace");
+		assert_eq!(
+			String::try_from(get_property(properties, "Slice").try_clone().unwrap()).unwrap(),
+			"machine.slice"
+		);
+		assert_eq!(
+			Vec::<u32>::try_from(get_property(properties, "PIDs").try_clone().unwrap()).unwrap(),
+			vec![1234]
+		);
+		assert!(bool::try_from(get_property(properties, "Delegate")).unwrap());
+		assert_eq!(
+			u64::try_from(get_property(properties, "MemoryMax")).unwrap(),
+			536870912
+		);
+		assert_eq!(
+			u64::try_from(get_property(properties, "CPUWeight")).unwrap(),
+			39
+		);
+		assert_eq!(
+			u64::try_from(get_property(properties, "TasksMax")).unwrap(),
+			100
+		);
+		assert_eq!(
+			Vec::<u8>::try_from(get_property(properties, "AllowedCPUs").try_clone().unwrap())
+				.unwrap(),
+			vec![0b0000_0111, 0b0000_0001]
+		);
+	}
+
+	#[test]
+	fn expand_slice_names() {
+		assert_eq!(expand_slice("-.slice"), PathBuf::new());
+		assert_eq!(expand_slice("system.slice"), PathBuf::from("system.slice"));
+		assert_eq!(
+			expand_slice("a-b-c.slice"),
+			PathBuf::from("a.slice/a-b.slice/a-b-c.slice")
+		);
+	}
+}
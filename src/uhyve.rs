@@ -0,0 +1,71 @@
+use crate::devices;
+use crate::hermit::{Architecture, NetworkConfig};
+use crate::hypervisor::Hypervisor;
+use crate::vmconfig::{VmConfig, VmNetwork};
+use std::os::unix::io::RawFd;
+use std::path::Path;
+
+/// uhyve is a minimal hypervisor, which is specialized on Hermit and loads the application
+/// directly into the VM.
+pub struct Uhyve;
+
+impl Hypervisor for Uhyve {
+	fn supports_architecture(&self, architecture: Architecture) -> bool {
+		architecture == Architecture::host() && architecture != Architecture::Riscv64
+	}
+
+	fn mount_devices(&self, rootfs: &Path) {
+		devices::mount_kvm(rootfs, true);
+	}
+
+	fn default_network(&self) -> VmNetwork {
+		VmNetwork::None
+	}
+
+	fn supports_network(&self, network: VmNetwork) -> bool {
+		network == VmNetwork::None
+	}
+
+	fn create_network(
+		&self,
+		_vm_config: &VmConfig,
+		_tokio_runtime: &tokio::runtime::Runtime,
+	) -> NetworkConfig {
+		NetworkConfig::None
+	}
+
+	fn get_args(
+		&self,
+		app: &str,
+		app_args: &[String],
+		_netconf: &NetworkConfig,
+		vm_config: &VmConfig,
+		_tap_fd: Option<RawFd>,
+	) -> Vec<String> {
+		if vm_config.micro_vm() {
+			warn!("uhyve always starts a minimal VM, so the microvm option is ignored!");
+		}
+		if vm_config.cpu_model().is_some() {
+			warn!("uhyve always passes the host CPU to the VM, so the CPU model is ignored!");
+		}
+		if vm_config.extra_kernel_args().is_some() {
+			warn!("uhyve does not support additional kernel arguments, so they are ignored!");
+		}
+
+		let mut exec_args: Vec<String> = vec![
+			"uhyve".to_string(),
+			"--cpu-count".to_string(),
+			vm_config.smp().to_string(),
+			"--memory-size".to_string(),
+			format!("{}MiB", vm_config.memory_size()),
+			app.to_string(),
+		];
+
+		if let Some(application_args) = app_args.get(1..) {
+			exec_args.push("--".to_string());
+			exec_args.extend(application_args.iter().cloned());
+		}
+
+		exec_args
+	}
+}
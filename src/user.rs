@@ -0,0 +1,87 @@
+use nix::sys::stat::Mode;
+use nix::unistd::{Gid, Uid};
+use oci_spec::runtime::User;
+use std::path::Path;
+
+/// Entry of the passwd database of the container
+struct PasswdEntry {
+	name: String,
+	uid: u32,
+	gid: u32,
+	home: String,
+}
+
+/// Reads /etc/passwd of the container. A missing or malformed database is treated as empty.
+fn read_passwd() -> Vec<PasswdEntry> {
+	let Ok(content) = std::fs::read_to_string(Path::new("/etc/passwd")) else {
+		return Vec::new();
+	};
+	content
+		.lines()
+		.filter_map(|line| {
+			// name:password:uid:gid:gecos:home:shell
+			let fields: Vec<&str> = line.split(':').collect();
+			if fields.len() < 7 {
+				return None;
+			}
+			Some(PasswdEntry {
+				name: fields[0].to_string(),
+				uid: fields[2].parse().ok()?,
+				gid: fields[3].parse().ok()?,
+				home: fields[5].to_string(),
+			})
+		})
+		.collect()
+}
+
+/// Changes the calling process to the user of the spec. If a username is given, uid and gid
+/// are taken from the passwd database of the container. The process loses all capabilities
+/// unless the keepcaps flag is set.
+pub fn switch_user(user: &User) {
+	let passwd = read_passwd();
+	let (uid, gid) = match user.username() {
+		Some(username) => {
+			let entry = passwd
+				.iter()
+				.find(|entry| entry.name == *username)
+				.unwrap_or_else(|| panic!("Could not find user {} in /etc/passwd", username));
+			(entry.uid, entry.gid)
+		}
+		None => (user.uid(), user.gid()),
+	};
+
+	// Programs expect HOME to point to the home directory of the user
+	if std::env::var_os("HOME").is_none() {
+		let home = passwd
+			.iter()
+			.find(|entry| entry.uid == uid)
+			.map_or("/", |entry| entry.home.as_str());
+		std::env::set_var("HOME", home);
+	}
+
+	if let Some(umask) = user.umask() {
+		let mode =
+			Mode::from_bits(umask).unwrap_or_else(|| panic!("Invalid umask {:#o} in spec!", umask));
+		let _ = nix::sys::stat::umask(mode);
+	}
+
+	// setgroups is not available in user namespaces, which deny it
+	let setgroups_allowed = std::fs::read_to_string("/proc/self/setgroups")
+		.map_or(true, |content| content.trim() != "deny");
+	let additional_gids = user.additional_gids().as_deref().unwrap_or_default();
+	if setgroups_allowed {
+		let groups: Vec<Gid> = additional_gids
+			.iter()
+			.map(|gid| Gid::from_raw(*gid))
+			.collect();
+		nix::unistd::setgroups(&groups).expect("Could not set additional group IDs!");
+	} else if !additional_gids.is_empty() {
+		panic!("Additional group IDs can not be set, because setgroups is denied!");
+	}
+
+	debug!("Switch to user {uid} with group {gid}");
+	let gid = Gid::from_raw(gid);
+	nix::unistd::setresgid(gid, gid, gid).expect("Could not set group ID");
+	let uid = Uid::from_raw(uid);
+	nix::unistd::setresuid(uid, uid, uid).expect("Could not set user ID");
+}
@@ -0,0 +1,87 @@
+use crate::paths;
+use nix::unistd::{Gid, Uid};
+use oci_spec::runtime::{Linux, LinuxIdMapping};
+use std::path::Path;
+
+fn format_mappings(mappings: &[LinuxIdMapping]) -> String {
+	mappings
+		.iter()
+		.map(|mapping| {
+			format!(
+				"{} {} {}\n",
+				mapping.container_id(),
+				mapping.host_id(),
+				mapping.size()
+			)
+		})
+		.collect()
+}
+
+/// Checks whether the mappings only map the given ID of the caller, which an unprivileged process
+/// is allowed to write on its own.
+fn maps_only(mappings: &[LinuxIdMapping], id: u32) -> bool {
+	mappings.len() == 1 && mappings[0].host_id() == id && mappings[0].size() == 1
+}
+
+/// Writes the mappings through the setuid helpers newuidmap and newgidmap of the shadow utils,
+/// which validate them against /etc/subuid and /etc/subgid.
+fn run_id_map_helper(helper: &str, pid: i32, mappings: &[LinuxIdMapping]) {
+	let helper_path = paths::find_in_path(Path::new(helper), None)
+		.unwrap_or_else(|| panic!("Could not find {} to setup the user namespace!", helper));
+	let mut cmd = std::process::Command::new(helper_path);
+	cmd.arg(pid.to_string());
+	for mapping in mappings {
+		cmd.args([
+			mapping.container_id().to_string(),
+			mapping.host_id().to_string(),
+			mapping.size().to_string(),
+		]);
+	}
+
+	let output = cmd
+		.output()
+		.unwrap_or_else(|err| panic!("Could not run {}: {}", helper, err));
+	if !output.status.success() {
+		panic!(
+			"{} returned exit status {}. Stderr: {}",
+			helper,
+			output.status,
+			String::from_utf8_lossy(&output.stderr)
+		);
+	}
+}
+
+/// Writes the uid and gid mappings of the new user namespace of the process `pid`. Unprivileged
+/// callers are only allowed to map their own IDs and have to fall back to newuidmap and newgidmap
+/// for everything else.
+pub fn write_id_mappings(pid: i32, linux: &Linux) {
+	let uid_mappings = linux.uid_mappings().as_deref().unwrap_or_default();
+	let gid_mappings = linux.gid_mappings().as_deref().unwrap_or_default();
+	if uid_mappings.is_empty() || gid_mappings.is_empty() {
+		panic!("A new user namespace requires uid and gid mappings!");
+	}
+
+	let proc_dir = Path::new("/proc").join(pid.to_string());
+	let privileged = Uid::effective().is_root();
+	debug!("Write uid mappings {uid_mappings:?} for process {pid}");
+	if privileged || maps_only(uid_mappings, Uid::effective().as_raw()) {
+		std::fs::write(proc_dir.join("uid_map"), format_mappings(uid_mappings))
+			.expect("Could not write uid_map!");
+	} else {
+		run_id_map_helper("newuidmap", pid, uid_mappings);
+	}
+
+	debug!("Write gid mappings {gid_mappings:?} for process {pid}");
+	if privileged {
+		std::fs::write(proc_dir.join("gid_map"), format_mappings(gid_mappings))
+			.expect("Could not write gid_map!");
+	} else if maps_only(gid_mappings, Gid::effective().as_raw()) {
+		// Unprivileged processes may only write the gid_map after giving up setgroups, as they
+		// could otherwise drop supplementary groups, which are used to deny access
+		std::fs::write(proc_dir.join("setgroups"), "deny").expect("Could not deny setgroups!");
+		std::fs::write(proc_dir.join("gid_map"), format_mappings(gid_mappings))
+			.expect("Could not write gid_map!");
+	} else {
+		run_id_map_helper("newgidmap", pid, gid_mappings);
+	}
+}
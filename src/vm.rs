@@ -0,0 +1,74 @@
+use crate::container::OCIContainer;
+use crate::hypervisor::HypervisorKind;
+use crate::{qmp, state};
+use clap::Subcommand;
+use serde_json::{json, Value};
+use std::fs::OpenOptions;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+/// Name of the link to the QMP socket of the VM in the container directory
+pub const QMP_SOCKET_LINK: &str = "qmp.sock";
+
+#[derive(Subcommand, Debug)]
+pub enum VmCommand {
+	/// Query the run state of the VM
+	Status,
+	/// Request a graceful shutdown of the unikernel through an ACPI power button event
+	SystemPowerdown,
+	/// Query the vCPUs of the VM
+	QueryCpus,
+	/// Query the memory size of the VM
+	QueryMemory,
+	/// Execute a command of the human monitor (HMP)
+	HumanMonitorCommand {
+		/// Command line of the HMP command, e.g. "info registers"
+		command_line: String,
+	},
+}
+
+pub fn vm_command(project_dir: PathBuf, id: &str, command: &VmCommand) {
+	let container_state = state::get_container_state(project_dir.clone(), id)
+		.unwrap_or_else(|| panic!("Container {} does not exist!", id));
+	if container_state.status != "running" && container_state.status != "paused" {
+		panic!(
+			"Container is not running, but has state \"{}\"!",
+			container_state.status
+		);
+	}
+
+	let container_dir = project_dir.join(id);
+	let container_file = OpenOptions::new()
+		.read(true)
+		.open(container_dir.join("container.json"))
+		.expect("Could not open container file!");
+	let container: OCIContainer = serde_json::from_reader(BufReader::new(container_file))
+		.expect("Could not parse container file!");
+	if container.hypervisor() != Some(HypervisorKind::Qemu) {
+		panic!("Container {} does not run a QEMU VM!", id);
+	}
+
+	let (qmp_command, arguments) = match command {
+		VmCommand::Status => ("query-status", None),
+		VmCommand::SystemPowerdown => ("system_powerdown", None),
+		VmCommand::QueryCpus => ("query-cpus-fast", None),
+		VmCommand::QueryMemory => ("query-memory-size-summary", None),
+		VmCommand::HumanMonitorCommand { command_line } => (
+			"human-monitor-command",
+			Some(json!({ "command-line": command_line })),
+		),
+	};
+
+	let mut connection = qmp::QmpConnection::connect(&container_dir.join(QMP_SOCKET_LINK))
+		.unwrap_or_else(|err| panic!("Could not connect to QMP socket of the VM: {}", err));
+	let result = connection
+		.execute(qmp_command, arguments)
+		.unwrap_or_else(|err| panic!("Could not execute {}: {}", qmp_command, err));
+
+	match result {
+		// The human monitor returns its output as plain text
+		Value::String(output) => print!("{output}"),
+		Value::Object(ref object) if object.is_empty() => {}
+		result => println!("{}", serde_json::to_string_pretty(&result).unwrap()),
+	}
+}
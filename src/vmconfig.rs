@@ -0,0 +1,259 @@
+use crate::cgroups;
+use crate::hermit::Architecture;
+use crate::hypervisor::{self, Hypervisor, HypervisorKind};
+use getset::{CopyGetters, Getters};
+use oci_spec::runtime::{LinuxResources, Spec};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Prefix of all annotations, which configure the virtual machine of a Hermit container
+pub const ANNOTATION_PREFIX: &str = "io.hermitcontainers.";
+
+/// Time (in seconds), which the unikernel gets to shut down after SIGTERM if not configured otherwise
+const DEFAULT_SHUTDOWN_GRACE_PERIOD: u64 = 10;
+
+/// Port, which is forwarded to the VM if user networking is used without an explicit port
+pub const DEFAULT_USER_PORT: u16 = 9975;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VmNetwork {
+	/// Connect the VM to the network namespace of the container through a macvtap device
+	Tap,
+	/// Use the user-mode network stack of QEMU and forward the given port
+	User(u16),
+	None,
+}
+
+#[derive(Clone, Debug, CopyGetters, Getters, Serialize, Deserialize)]
+/// Configuration of the virtual machine of a Hermit container. It is determined during create from
+/// the runh configuration file, the resource limits and the annotations of the container.
+pub struct VmConfig {
+	#[get_copy = "pub"]
+	/// Hypervisor, which runs the VM
+	hypervisor: HypervisorKind,
+
+	#[get_copy = "pub"]
+	/// Architecture of the Hermit application
+	architecture: Architecture,
+
+	#[get_copy = "pub"]
+	/// Number of vCPUs
+	smp: u32,
+
+	#[get_copy = "pub"]
+	/// Memory size in MiB
+	memory_size: u64,
+
+	#[get_copy = "pub"]
+	/// Whether KVM should be used, if available
+	kvm: bool,
+
+	#[get_copy = "pub"]
+	/// Whether the VM should be started as a microvm without PCI and virtiofs
+	micro_vm: bool,
+
+	#[get_copy = "pub"]
+	network: VmNetwork,
+
+	#[get = "pub"]
+	/// CPU model passed to QEMU, which replaces the default model
+	cpu_model: Option<String>,
+
+	#[get = "pub"]
+	/// Additional arguments for the Hermit kernel
+	extra_kernel_args: Option<String>,
+
+	#[get_copy = "pub"]
+	/// Time (in seconds) between the request to shut down the unikernel and killing the VM
+	shutdown_grace_period: u64,
+}
+
+/// Determines the number of vCPUs from the CPU quota and cpuset of the container.
+fn get_resource_cpus(resources: Option<&LinuxResources>) -> Option<u32> {
+	let cpu = resources.and_then(|resources| resources.cpu().as_ref());
+	let quota_cpus = cpu.and_then(|cpu| {
+		let quota = cpu.quota().filter(|quota| *quota > 0)?;
+		let period = cpu.period().unwrap_or(100000);
+		Some((quota as u64).div_ceil(period) as u32)
+	});
+	let cpuset_cpus = cpu
+		.and_then(|cpu| cpu.cpus().as_ref())
+		.map(|cpus| cgroups::parse_cpuset(cpus).len() as u32)
+		.filter(|cpus| *cpus > 0);
+
+	match (quota_cpus, cpuset_cpus) {
+		(Some(quota_cpus), Some(cpuset_cpus)) => Some(quota_cpus.min(cpuset_cpus)),
+		(cpus, None) | (None, cpus) => cpus,
+	}
+}
+
+/// Determines the memory size (in MiB) of the VM from the memory limit of the container,
+/// leaving room for the hypervisor itself.
+fn get_resource_memory_size(
+	resources: Option<&LinuxResources>,
+	kind: HypervisorKind,
+	hypervisor: &dyn Hypervisor,
+) -> Option<u64> {
+	let limit = resources
+		.and_then(|resources| resources.memory().as_ref())
+		.and_then(|memory| memory.limit())
+		.filter(|limit| *limit > 0)?;
+
+	let overhead = hypervisor.memory_overhead();
+	let limit = limit as u64 / (1024 * 1024);
+	if limit <= overhead {
+		panic!(
+			"Memory limit of {} MiB does not leave any memory for the VM besides the overhead of {:?} ({} MiB)!",
+			limit, kind, overhead
+		);
+	}
+	Some(limit - overhead)
+}
+
+/// Parses the value of an annotation (or of a deprecated environment variable)
+fn parse_annotation<T: std::str::FromStr>(key: &str, value: &str) -> T {
+	value
+		.parse()
+		.unwrap_or_else(|_| panic!("Invalid value {} for annotation {}!", value, key))
+}
+
+fn parse_network(key: &str, value: &str) -> VmNetwork {
+	match value.split_once(':') {
+		None if value == "tap" => VmNetwork::Tap,
+		None if value == "user" => VmNetwork::User(DEFAULT_USER_PORT),
+		None if value == "none" => VmNetwork::None,
+		Some(("user", port)) => VmNetwork::User(parse_annotation(key, port)),
+		_ => panic!(
+			"Invalid value {} for annotation {}! Expected tap, user[:<port>] or none.",
+			value, key
+		),
+	}
+}
+
+impl VmConfig {
+	/// Merges the annotations `io.hermitcontainers.*` of the container over the resource limits
+	/// and the runh configuration file. Invalid annotations are rejected.
+	pub fn new(spec: &Spec, architecture: Architecture, rootfs: &Path) -> Self {
+		let resources = spec
+			.linux()
+			.as_ref()
+			.and_then(|linux| linux.resources().as_ref());
+
+		let mut vm_config = Self {
+			hypervisor: crate::CONFIG.hypervisor.unwrap_or_default(),
+			architecture,
+			smp: get_resource_cpus(resources)
+				.or(crate::CONFIG.smp)
+				.unwrap_or(1),
+			// Determined below, as the overhead depends on the hypervisor
+			memory_size: 0,
+			kvm: crate::CONFIG.kvm.unwrap_or(false),
+			micro_vm: false,
+			// Determined below, as the default depends on the hypervisor
+			network: VmNetwork::None,
+			cpu_model: None,
+			extra_kernel_args: None,
+			shutdown_grace_period: crate::CONFIG
+				.shutdown_grace_period
+				.unwrap_or(DEFAULT_SHUTDOWN_GRACE_PERIOD),
+		};
+
+		let mut memory_size = None;
+		let mut network = None;
+
+		// Deprecated environment variables of earlier runh versions, which are set in the
+		// environment of the container and overridden by the annotations
+		let env = spec
+			.process()
+			.as_ref()
+			.and_then(|process| process.env().as_ref());
+		for (name, value) in env
+			.iter()
+			.flat_map(|env| env.iter())
+			.filter_map(|var| var.split_once('='))
+		{
+			match name {
+				"RUNH_MICRO_VM" => {
+					warn!("RUNH_MICRO_VM is deprecated, use the annotation {ANNOTATION_PREFIX}microvm instead!");
+					vm_config.micro_vm = parse_annotation::<u32>(name, value) > 0;
+				}
+				"RUNH_USER_PORT" => {
+					warn!("RUNH_USER_PORT is deprecated, use the annotation {ANNOTATION_PREFIX}network instead!");
+					network = Some(match parse_annotation(name, value) {
+						0 => VmNetwork::Tap,
+						port => VmNetwork::User(port),
+					});
+				}
+				_ => {}
+			}
+		}
+
+		for (key, value) in spec.annotations().iter().flatten() {
+			let Some(name) = key.strip_prefix(ANNOTATION_PREFIX) else {
+				continue;
+			};
+			match name {
+				"hypervisor" => vm_config.hypervisor = parse_annotation(key, value),
+				"smp" => vm_config.smp = parse_annotation(key, value),
+				"memory" => memory_size = Some(parse_annotation(key, value)),
+				"kvm" => vm_config.kvm = parse_annotation(key, value),
+				"microvm" => vm_config.micro_vm = parse_annotation(key, value),
+				"network" => network = Some(parse_network(key, value)),
+				"cpu-model" => vm_config.cpu_model = Some(value.clone()),
+				"extra-kernel-args" => vm_config.extra_kernel_args = Some(value.clone()),
+				"shutdown-grace-period" => {
+					vm_config.shutdown_grace_period = parse_annotation(key, value)
+				}
+				_ => panic!("Unknown annotation {}!", key),
+			}
+		}
+
+		let hypervisor = hypervisor::get_hypervisor(vm_config.hypervisor);
+		let max_memory_size =
+			get_resource_memory_size(resources, vm_config.hypervisor, hypervisor.as_ref());
+		vm_config.memory_size = memory_size
+			.or(max_memory_size)
+			.or(crate::CONFIG.memory_size)
+			.unwrap_or(1024);
+		vm_config.network = network.unwrap_or_else(|| hypervisor.default_network());
+
+		if vm_config.smp == 0 {
+			panic!("The VM requires at least one vCPU!");
+		}
+		if vm_config.memory_size == 0 {
+			panic!("The VM requires a memory size larger than 0!");
+		}
+		// The VM must not exceed the memory limit of the container, as it would be killed otherwise
+		if let Some(max_memory_size) = max_memory_size {
+			if vm_config.memory_size > max_memory_size {
+				panic!(
+					"The VM memory of {} MiB exceeds the memory limit of the container minus the overhead of {:?} ({} MiB)!",
+					vm_config.memory_size, vm_config.hypervisor, max_memory_size
+				);
+			}
+		}
+		if !hypervisor.supports_architecture(architecture) {
+			panic!(
+				"The hypervisor {:?} is not able to run {} applications on this {} host!",
+				vm_config.hypervisor,
+				architecture,
+				Architecture::host()
+			);
+		}
+		hypervisor.check_installation(&vm_config, rootfs);
+		if !hypervisor.supports_network(vm_config.network) {
+			panic!(
+				"The hypervisor {:?} does not support the network {:?}! Use the annotation {}network to select another network.",
+				vm_config.hypervisor, vm_config.network, ANNOTATION_PREFIX
+			);
+		}
+		if vm_config.micro_vm
+			&& vm_config.hypervisor == HypervisorKind::Qemu
+			&& architecture != Architecture::X86_64
+		{
+			panic!("The microvm machine of QEMU is only available for x86_64 applications!");
+		}
+
+		vm_config
+	}
+}